@@ -75,7 +75,7 @@ fn main() {
 
     let conn = Connect::open(uri.as_deref()).unwrap();
     let dom = conn.lookup_domain_by_name(&name).unwrap();
-    let mut st = Stream::new(&conn, VIR_STREAM_NONBLOCK).unwrap();
+    let st = Stream::new(&conn, VIR_STREAM_NONBLOCK).unwrap();
     dom.open_console(dev_name.as_deref(), &st, VIR_DOMAIN_CONSOLE_FORCE)
         .unwrap();
 
@@ -0,0 +1,60 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <https://www.gnu.org/licenses/>.
+ */
+
+//! Plumbing shared by the C callbacks handed over to libvirt, whose
+//! opaque data is a boxed Rust closure.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::{mem, process};
+
+use crate::connect::Connect;
+
+/// Runs `f`, aborting the process if it panics.
+///
+/// Unwinding out of a function called by C is undefined behaviour, so
+/// every callback invoked by libvirt must go through this.
+pub(crate) fn abort_on_panic<R>(f: impl FnOnce() -> R) -> R {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(_) => process::abort(),
+    }
+}
+
+/// Invokes the closure stored in `opaque` for an event about `obj`,
+/// built by `event`.
+///
+/// The event is built under the same panic guard as the closure runs,
+/// since parsing what libvirt hands over may panic too.
+///
+/// libvirt only lends its references on the connection and the object
+/// for the duration of the callback, so the wrappers must not release
+/// them when dropped.
+pub(crate) unsafe fn event_dispatch<O, E, F: FnMut(&Connect, &O, E)>(
+    conn: sys::virConnectPtr,
+    obj: O,
+    opaque: *mut libc::c_void,
+    event: impl FnOnce() -> E,
+) {
+    let cb = &mut *(opaque as *mut F);
+    let conn = mem::ManuallyDrop::new(Connect::from_ptr(conn));
+    let obj = mem::ManuallyDrop::new(obj);
+    abort_on_panic(|| cb(&conn, &obj, event()));
+}
+
+/// Free callback releasing the closure boxed into `opaque`.
+pub(crate) unsafe extern "C" fn callback_free<F>(opaque: *mut libc::c_void) {
+    abort_on_panic(|| drop(Box::from_raw(opaque as *mut F)));
+}
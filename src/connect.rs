@@ -22,16 +22,34 @@ use std::{mem, ptr, str};
 
 use uuid::Uuid;
 
-use crate::domain::{Domain, DomainStatsRecord};
-use crate::enumutil::{impl_enum, RawEnum};
+use crate::callback::{abort_on_panic, callback_free};
+use crate::domain::{
+    domain_event_callback, Domain, DomainEvent, DomainEventCallback, DomainEventId,
+    DomainStatsFlags, DomainStatsRecord, DomainStatsTypes,
+};
+use crate::enumutil::{impl_enum, Enum, RawEnum};
 use crate::error::Error;
+#[cfg(feature = "tokio")]
+use crate::error::ErrorNumber;
 use crate::interface::Interface;
-use crate::network::Network;
-use crate::nodedev::NodeDevice;
+use crate::network::{
+    network_event_callback, Network, NetworkEvent, NetworkEventCallback, NetworkEventId,
+};
+use crate::nodedev::{
+    node_device_event_callback, NodeDevice, NodeDeviceEvent, NodeDeviceEventCallback,
+    NodeDeviceEventId,
+};
 use crate::nwfilter::NWFilter;
-use crate::secret::Secret;
-use crate::storage_pool::StoragePool;
+use crate::secret::{
+    secret_event_callback, Secret, SecretEvent, SecretEventCallback, SecretEventId,
+};
+use crate::storage_pool::{
+    storage_pool_event_callback, StoragePool, StoragePoolEvent, StoragePoolEventCallback,
+    StoragePoolEventId,
+};
 use crate::storage_vol::StorageVol;
+#[cfg(feature = "tokio")]
+use crate::tokio_event::DomainEventReceiver;
 use crate::util::{c_ulong_to_u64, check_neg, check_null, check_zero};
 
 extern "C" fn connect_callback(
@@ -74,6 +92,19 @@ extern "C" fn connect_callback(
     0
 }
 
+unsafe extern "C" fn close_callback<F: FnMut(&Connect, CloseReasonEnum)>(
+    conn: sys::virConnectPtr,
+    reason: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let cb = &mut *(opaque as *mut F);
+    // libvirt only lends us its reference for the duration of the
+    // callback, so the wrapper must not release it when dropped.
+    let conn = mem::ManuallyDrop::new(Connect::from_ptr(conn));
+    let reason = CloseReasonEnum::from_raw(reason as sys::virConnectCloseReason);
+    abort_on_panic(|| cb(&conn, reason));
+}
+
 #[derive(Clone, Debug)]
 pub struct NodeInfo {
     /// Indicating the CPU model.
@@ -99,6 +130,14 @@ pub struct NodeInfo {
     pub threads: u32,
 }
 
+impl NodeInfo {
+    /// Returns the number of CPUs present on the host, including the
+    /// offline ones.
+    pub fn max_cpus(&self) -> u32 {
+        self.nodes * self.sockets * self.cores * self.threads
+    }
+}
+
 // TODO(sahid): should support closure
 pub type ConnectAuthCallback = fn(creds: &mut Vec<ConnectCredential>);
 
@@ -167,6 +206,33 @@ impl_enum! {
     }
 }
 
+/// Reason a connection was closed, passed to the callback registered
+/// with [`Connect::register_close_callback`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CloseReason {
+    /// Misc I/O error.
+    Error,
+    /// End-of-file from server.
+    Eof,
+    /// Keepalive timer triggered.
+    KeepAlive,
+    /// Client requested it.
+    Client,
+}
+
+pub type CloseReasonEnum = Enum<CloseReason, sys::virConnectCloseReason>;
+
+impl_enum! {
+    enum: CloseReason,
+    raw: sys::virConnectCloseReason,
+    match: {
+        sys::VIR_CONNECT_CLOSE_REASON_ERROR => Error,
+        sys::VIR_CONNECT_CLOSE_REASON_EOF => Eof,
+        sys::VIR_CONNECT_CLOSE_REASON_KEEPALIVE => KeepAlive,
+        sys::VIR_CONNECT_CLOSE_REASON_CLIENT => Client,
+    }
+}
+
 pub struct ConnectAuth {
     /// List of supported `ConnectCredentialType` values.
     creds: Vec<ConnectCredentialType>,
@@ -186,10 +252,16 @@ impl ConnectAuth {
 #[derive(Debug)]
 pub struct Connect {
     ptr: sys::virConnectPtr,
+    close_callback: sys::virConnectCloseFunc,
 }
 
 impl Drop for Connect {
     fn drop(&mut self) {
+        if self.close_callback.is_some() {
+            // libvirt holds a reference on the connection for as long
+            // as the close callback is registered.
+            let _ = self.unregister_close_callback();
+        }
         if let Err(e) = check_neg!(unsafe { sys::virConnectClose(self.as_ptr()) }) {
             panic!("Unable to drop reference on connection: {e}")
         }
@@ -247,7 +319,10 @@ impl Connect {
     /// The rust wrapper will own the reference count
     /// for the C object upon return.
     pub unsafe fn from_ptr(ptr: sys::virConnectPtr) -> Connect {
-        Connect { ptr }
+        Connect {
+            ptr,
+            close_callback: None,
+        }
     }
 
     /// Get the local library version number
@@ -1073,6 +1148,55 @@ impl Connect {
         Ok(())
     }
 
+    /// Registers a callback to be invoked when the connection is
+    /// closed, e.g. because the remote daemon went away or the
+    /// keep-alive timer expired. Only one close callback can be
+    /// registered per connection.
+    ///
+    /// The callback is unregistered when this object is dropped,
+    /// clones of the connection do not keep it alive.
+    ///
+    /// An event loop implementation must be registered, see
+    /// [`crate::event::event_register_default_impl`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-host.html#virConnectRegisterCloseCallback>
+    pub fn register_close_callback<F: 'static + Send + FnMut(&Connect, CloseReasonEnum)>(
+        &mut self,
+        cb: F,
+    ) -> Result<(), Error> {
+        let opaque = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+        let ret = unsafe {
+            sys::virConnectRegisterCloseCallback(
+                self.as_ptr(),
+                Some(close_callback::<F>),
+                opaque,
+                Some(callback_free::<F>),
+            )
+        };
+        if ret == -1 {
+            let err = Error::last_error();
+            // libvirt does not call the free callback on failure.
+            unsafe { callback_free::<F>(opaque) };
+            return Err(err);
+        }
+        self.close_callback = Some(close_callback::<F>);
+        Ok(())
+    }
+
+    /// Unregisters the callback added with
+    /// [`Self::register_close_callback`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-host.html#virConnectUnregisterCloseCallback>
+    pub fn unregister_close_callback(&mut self) -> Result<(), Error> {
+        let _ = check_neg!(unsafe {
+            sys::virConnectUnregisterCloseCallback(self.as_ptr(), self.close_callback)
+        })?;
+        // Only forget the callback once libvirt released it, so that
+        // dropping the connection retries after a failure.
+        self.close_callback = None;
+        Ok(())
+    }
+
     /// Returns libvirt XML corresponding to a hypervisor
     /// native configuration file
     ///
@@ -1147,33 +1271,26 @@ impl Connect {
         Ok(unsafe { c_chars_to_string!(ret) })
     }
 
-    /// Returns the domain statistics
+    /// Returns the statistics of all domains, parsed in the groups
+    /// selected by `stats`.
     ///
     /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virConnectGetAllDomainStats>
     pub fn all_domain_stats(
         &self,
-        stats: u32,
-        flags: u32,
+        stats: DomainStatsTypes,
+        flags: DomainStatsFlags,
     ) -> Result<Vec<DomainStatsRecord>, Error> {
         let mut record: *mut sys::virDomainStatsRecordPtr = ptr::null_mut();
         let size = check_neg!(unsafe {
             sys::virConnectGetAllDomainStats(
                 self.as_ptr(),
-                stats as libc::c_uint,
+                stats.to_raw() as libc::c_uint,
                 &mut record,
-                flags as libc::c_uint,
+                flags.to_raw() as libc::c_uint,
             )
         })?;
 
-        let mut array: Vec<DomainStatsRecord> = Vec::new();
-        for x in 0..size as isize {
-            array.push(DomainStatsRecord {
-                ptr: unsafe { *record.offset(x) },
-            });
-        }
-        unsafe { libc::free(record as *mut libc::c_void) };
-
-        Ok(array)
+        Ok(unsafe { DomainStatsRecord::from_list(record, size as usize) })
     }
 
     /// Determine the baseline between multiple CPU definitions
@@ -1793,4 +1910,310 @@ impl Connect {
         })?;
         Ok(unsafe { StorageVol::from_ptr(ptr) })
     }
+
+    /// Adds a callback to receive notifications of arbitrary domain
+    /// events occurring on a domain, or on all domains when `dom` is
+    /// `None`. The callback stays registered until the returned
+    /// handle is dropped.
+    ///
+    /// An event loop implementation must be registered, see
+    /// [`crate::event::event_register_default_impl`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virConnectDomainEventRegisterAny>
+    pub fn domain_event_register_any<F: 'static + Send + FnMut(&Connect, &Domain, DomainEvent)>(
+        &self,
+        dom: Option<&Domain>,
+        event_id: DomainEventId,
+        cb: F,
+    ) -> Result<DomainEventCallback, Error> {
+        let dom_ptr = match dom {
+            Some(d) => unsafe { d.as_ptr() },
+            None => ptr::null_mut(),
+        };
+        let opaque = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+        let ret = unsafe {
+            sys::virConnectDomainEventRegisterAny(
+                self.as_ptr(),
+                dom_ptr,
+                event_id.to_raw() as libc::c_int,
+                domain_event_callback::<F>(event_id),
+                opaque,
+                Some(callback_free::<F>),
+            )
+        };
+        if ret == -1 {
+            let err = Error::last_error();
+            // libvirt does not call the free callback on failure.
+            unsafe { callback_free::<F>(opaque) };
+            return Err(err);
+        }
+        Ok(EventCallback::new(
+            self.clone(),
+            ret,
+            sys::virConnectDomainEventDeregisterAny,
+        ))
+    }
+
+    /// Subscribes to domain events, delivered through the returned
+    /// receiver rather than a callback.
+    ///
+    /// Requires the `tokio` feature, events are dispatched by
+    /// [`crate::tokio_event::TokioEventLoop`] or any other running
+    /// event loop implementation.
+    #[cfg(feature = "tokio")]
+    pub fn domain_event_receiver(
+        &self,
+        dom: Option<&Domain>,
+        event_id: DomainEventId,
+    ) -> Result<DomainEventReceiver, Error> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let callback = self.domain_event_register_any(dom, event_id, move |_, dom, event| {
+            let _ = tx.send((dom.clone(), event));
+        })?;
+        Ok(DomainEventReceiver::new(callback, rx))
+    }
+
+    /// Waits for the next domain event of type `event_id`, on `dom`
+    /// or on any domain when `None`.
+    ///
+    /// Requires the `tokio` feature, see
+    /// [`Self::domain_event_receiver`].
+    #[cfg(feature = "tokio")]
+    pub async fn domain_event(
+        &self,
+        dom: Option<&Domain>,
+        event_id: DomainEventId,
+    ) -> Result<(Domain, DomainEvent), Error> {
+        let mut events = self.domain_event_receiver(dom, event_id)?;
+        events.recv().await.ok_or_else(|| {
+            Error::new(
+                ErrorNumber::InternalError,
+                "domain event callback released before any event".into(),
+            )
+        })
+    }
+
+    /// Adds a callback to receive notifications of arbitrary network
+    /// events occurring on a network, or on all networks when `net`
+    /// is `None`. The callback stays registered until the returned
+    /// handle is dropped.
+    ///
+    /// An event loop implementation must be registered, see
+    /// [`crate::event::event_register_default_impl`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-network.html#virConnectNetworkEventRegisterAny>
+    pub fn network_event_register_any<
+        F: 'static + Send + FnMut(&Connect, &Network, NetworkEvent),
+    >(
+        &self,
+        net: Option<&Network>,
+        event_id: NetworkEventId,
+        cb: F,
+    ) -> Result<NetworkEventCallback, Error> {
+        let net_ptr = match net {
+            Some(n) => unsafe { n.as_ptr() },
+            None => ptr::null_mut(),
+        };
+        let opaque = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+        let ret = unsafe {
+            sys::virConnectNetworkEventRegisterAny(
+                self.as_ptr(),
+                net_ptr,
+                event_id.to_raw() as libc::c_int,
+                network_event_callback::<F>(event_id),
+                opaque,
+                Some(callback_free::<F>),
+            )
+        };
+        if ret == -1 {
+            let err = Error::last_error();
+            // libvirt does not call the free callback on failure.
+            unsafe { callback_free::<F>(opaque) };
+            return Err(err);
+        }
+        Ok(EventCallback::new(
+            self.clone(),
+            ret,
+            sys::virConnectNetworkEventDeregisterAny,
+        ))
+    }
+
+    /// Adds a callback to receive notifications of arbitrary storage
+    /// pool events occurring on a pool, or on all pools when `pool`
+    /// is `None`. The callback stays registered until the returned
+    /// handle is dropped.
+    ///
+    /// An event loop implementation must be registered, see
+    /// [`crate::event::event_register_default_impl`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-storage.html#virConnectStoragePoolEventRegisterAny>
+    pub fn storage_pool_event_register_any<
+        F: 'static + Send + FnMut(&Connect, &StoragePool, StoragePoolEvent),
+    >(
+        &self,
+        pool: Option<&StoragePool>,
+        event_id: StoragePoolEventId,
+        cb: F,
+    ) -> Result<StoragePoolEventCallback, Error> {
+        let pool_ptr = match pool {
+            Some(p) => unsafe { p.as_ptr() },
+            None => ptr::null_mut(),
+        };
+        let opaque = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+        let ret = unsafe {
+            sys::virConnectStoragePoolEventRegisterAny(
+                self.as_ptr(),
+                pool_ptr,
+                event_id.to_raw() as libc::c_int,
+                storage_pool_event_callback::<F>(event_id),
+                opaque,
+                Some(callback_free::<F>),
+            )
+        };
+        if ret == -1 {
+            let err = Error::last_error();
+            // libvirt does not call the free callback on failure.
+            unsafe { callback_free::<F>(opaque) };
+            return Err(err);
+        }
+        Ok(EventCallback::new(
+            self.clone(),
+            ret,
+            sys::virConnectStoragePoolEventDeregisterAny,
+        ))
+    }
+
+    /// Adds a callback to receive notifications of arbitrary node device
+    /// events occurring on a node device, or on all node devices when `dev`
+    /// is `None`. The callback stays registered until the returned
+    /// handle is dropped.
+    ///
+    /// An event loop implementation must be registered, see
+    /// [`crate::event::event_register_default_impl`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-nodedev.html#virConnectNodeDeviceEventRegisterAny>
+    pub fn node_device_event_register_any<
+        F: 'static + Send + FnMut(&Connect, &NodeDevice, NodeDeviceEvent),
+    >(
+        &self,
+        dev: Option<&NodeDevice>,
+        event_id: NodeDeviceEventId,
+        cb: F,
+    ) -> Result<NodeDeviceEventCallback, Error> {
+        let dev_ptr = match dev {
+            Some(d) => unsafe { d.as_ptr() },
+            None => ptr::null_mut(),
+        };
+        let opaque = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+        let ret = unsafe {
+            sys::virConnectNodeDeviceEventRegisterAny(
+                self.as_ptr(),
+                dev_ptr,
+                event_id.to_raw() as libc::c_int,
+                node_device_event_callback::<F>(event_id),
+                opaque,
+                Some(callback_free::<F>),
+            )
+        };
+        if ret == -1 {
+            let err = Error::last_error();
+            // libvirt does not call the free callback on failure.
+            unsafe { callback_free::<F>(opaque) };
+            return Err(err);
+        }
+        Ok(EventCallback::new(
+            self.clone(),
+            ret,
+            sys::virConnectNodeDeviceEventDeregisterAny,
+        ))
+    }
+
+    /// Adds a callback to receive notifications of arbitrary secret
+    /// events occurring on a secret, or on all secrets when `secret`
+    /// is `None`. The callback stays registered until the returned
+    /// handle is dropped.
+    ///
+    /// An event loop implementation must be registered, see
+    /// [`crate::event::event_register_default_impl`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-secret.html#virConnectSecretEventRegisterAny>
+    pub fn secret_event_register_any<F: 'static + Send + FnMut(&Connect, &Secret, SecretEvent)>(
+        &self,
+        secret: Option<&Secret>,
+        event_id: SecretEventId,
+        cb: F,
+    ) -> Result<SecretEventCallback, Error> {
+        let secret_ptr = match secret {
+            Some(s) => unsafe { s.as_ptr() },
+            None => ptr::null_mut(),
+        };
+        let opaque = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+        let ret = unsafe {
+            sys::virConnectSecretEventRegisterAny(
+                self.as_ptr(),
+                secret_ptr,
+                event_id.to_raw() as libc::c_int,
+                secret_event_callback::<F>(event_id),
+                opaque,
+                Some(callback_free::<F>),
+            )
+        };
+        if ret == -1 {
+            let err = Error::last_error();
+            // libvirt does not call the free callback on failure.
+            unsafe { callback_free::<F>(opaque) };
+            return Err(err);
+        }
+        Ok(EventCallback::new(
+            self.clone(),
+            ret,
+            sys::virConnectSecretEventDeregisterAny,
+        ))
+    }
+}
+
+type EventDeregisterAnyFn = unsafe extern "C" fn(sys::virConnectPtr, libc::c_int) -> libc::c_int;
+
+/// Registration of an event callback, returned by the
+/// `*_event_register_any` methods of [`Connect`].
+///
+/// The callback is deregistered when this handle is dropped.
+#[derive(Debug)]
+pub struct EventCallback {
+    conn: Connect,
+    id: libc::c_int,
+    deregister_any: EventDeregisterAnyFn,
+}
+
+impl Drop for EventCallback {
+    fn drop(&mut self) {
+        if self.id >= 0 {
+            // Errors cannot be reported from drop, use deregister()
+            // to observe them.
+            unsafe { (self.deregister_any)(self.conn.as_ptr(), self.id) };
+        }
+    }
+}
+
+impl EventCallback {
+    fn new(conn: Connect, id: libc::c_int, deregister_any: EventDeregisterAnyFn) -> EventCallback {
+        EventCallback {
+            conn,
+            id,
+            deregister_any,
+        }
+    }
+
+    /// Returns the callback ID assigned by libvirt.
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    /// Removes the callback, using the `virConnect*EventDeregisterAny`
+    /// function matching the kind of event it was registered for.
+    pub fn deregister(mut self) -> Result<(), Error> {
+        let id = mem::replace(&mut self.id, -1);
+        let _ = check_neg!(unsafe { (self.deregister_any)(self.conn.as_ptr(), id) })?;
+        Ok(())
+    }
 }
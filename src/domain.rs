@@ -16,17 +16,24 @@
  * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
  */
 
+use std::collections::HashMap;
 use std::ffi::CString;
 use std::fmt::{Display, Formatter, Result as FmtResult};
-use std::{mem, ptr, str};
+use std::str::FromStr;
+use std::{mem, ptr, slice, str};
 use uuid::Uuid;
 
-use crate::connect::Connect;
+use crate::callback::event_dispatch;
+use crate::connect::{Connect, EventCallback, NodeInfo};
+use crate::domain_checkpoint::{
+    DomainCheckpoint, DomainCheckpointCreateFlags, DomainCheckpointListFlags,
+};
 use crate::domain_snapshot::DomainSnapshot;
-use crate::enumutil::{impl_enum, Enum};
-use crate::error::Error;
+use crate::enumutil::{impl_enum, impl_flags, Enum};
+use crate::error::{Error, ErrorNumber};
 use crate::stream::Stream;
-use crate::typedparams::{from_params, to_params};
+pub use crate::typedparams::TypedParamValue;
+use crate::typedparams::{from_params, from_params_generic, to_params};
 use crate::util::{c_ulong_to_u64, check_neg, check_null, check_zero};
 use crate::{param_field_in, param_field_out};
 
@@ -279,6 +286,341 @@ impl Display for DomainStateReason {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventId {
+    Lifecycle,
+    Reboot,
+    RtcChange,
+    Watchdog,
+    IOError,
+    Graphics,
+    IOErrorReason,
+    ControlError,
+    BlockJob,
+    DiskChange,
+    TrayChange,
+    PMWakeup,
+    PMSuspend,
+    BalloonChange,
+    PMSuspendDisk,
+    DeviceRemoved,
+    BlockJob2,
+    Tunable,
+    AgentLifecycle,
+    DeviceAdded,
+    MigrationIteration,
+    JobCompleted,
+    DeviceRemovalFailed,
+    MetadataChange,
+    BlockThreshold,
+    MemoryFailure,
+    MemoryDeviceSizeChange,
+    NICMACChange,
+}
+
+pub type DomainEventIdEnum = Enum<DomainEventId, sys::virDomainEventID>;
+
+impl_enum! {
+    enum: DomainEventId,
+    raw: sys::virDomainEventID,
+    match: {
+    sys::VIR_DOMAIN_EVENT_ID_LIFECYCLE => Lifecycle,
+    sys::VIR_DOMAIN_EVENT_ID_REBOOT => Reboot,
+    sys::VIR_DOMAIN_EVENT_ID_RTC_CHANGE => RtcChange,
+    sys::VIR_DOMAIN_EVENT_ID_WATCHDOG => Watchdog,
+    sys::VIR_DOMAIN_EVENT_ID_IO_ERROR => IOError,
+    sys::VIR_DOMAIN_EVENT_ID_GRAPHICS => Graphics,
+    sys::VIR_DOMAIN_EVENT_ID_IO_ERROR_REASON => IOErrorReason,
+    sys::VIR_DOMAIN_EVENT_ID_CONTROL_ERROR => ControlError,
+    sys::VIR_DOMAIN_EVENT_ID_BLOCK_JOB => BlockJob,
+    sys::VIR_DOMAIN_EVENT_ID_DISK_CHANGE => DiskChange,
+    sys::VIR_DOMAIN_EVENT_ID_TRAY_CHANGE => TrayChange,
+    sys::VIR_DOMAIN_EVENT_ID_PMWAKEUP => PMWakeup,
+    sys::VIR_DOMAIN_EVENT_ID_PMSUSPEND => PMSuspend,
+    sys::VIR_DOMAIN_EVENT_ID_BALLOON_CHANGE => BalloonChange,
+    sys::VIR_DOMAIN_EVENT_ID_PMSUSPEND_DISK => PMSuspendDisk,
+    sys::VIR_DOMAIN_EVENT_ID_DEVICE_REMOVED => DeviceRemoved,
+    sys::VIR_DOMAIN_EVENT_ID_BLOCK_JOB_2 => BlockJob2,
+    sys::VIR_DOMAIN_EVENT_ID_TUNABLE => Tunable,
+    sys::VIR_DOMAIN_EVENT_ID_AGENT_LIFECYCLE => AgentLifecycle,
+    sys::VIR_DOMAIN_EVENT_ID_DEVICE_ADDED => DeviceAdded,
+    sys::VIR_DOMAIN_EVENT_ID_MIGRATION_ITERATION => MigrationIteration,
+    sys::VIR_DOMAIN_EVENT_ID_JOB_COMPLETED => JobCompleted,
+    sys::VIR_DOMAIN_EVENT_ID_DEVICE_REMOVAL_FAILED => DeviceRemovalFailed,
+    sys::VIR_DOMAIN_EVENT_ID_METADATA_CHANGE => MetadataChange,
+    sys::VIR_DOMAIN_EVENT_ID_BLOCK_THRESHOLD => BlockThreshold,
+    sys::VIR_DOMAIN_EVENT_ID_MEMORY_FAILURE => MemoryFailure,
+    sys::VIR_DOMAIN_EVENT_ID_MEMORY_DEVICE_SIZE_CHANGE => MemoryDeviceSizeChange,
+    sys::VIR_DOMAIN_EVENT_ID_NIC_MAC_CHANGE => NICMACChange,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventType {
+    Defined,
+    Undefined,
+    Started,
+    Suspended,
+    Resumed,
+    Stopped,
+    Shutdown,
+    PMSuspended,
+    Crashed,
+}
+
+pub type DomainEventTypeEnum = Enum<DomainEventType, sys::virDomainEventType>;
+
+impl_enum! {
+    enum: DomainEventType,
+    raw: sys::virDomainEventType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_DEFINED => Defined,
+    sys::VIR_DOMAIN_EVENT_UNDEFINED => Undefined,
+    sys::VIR_DOMAIN_EVENT_STARTED => Started,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED => Suspended,
+    sys::VIR_DOMAIN_EVENT_RESUMED => Resumed,
+    sys::VIR_DOMAIN_EVENT_STOPPED => Stopped,
+    sys::VIR_DOMAIN_EVENT_SHUTDOWN => Shutdown,
+    sys::VIR_DOMAIN_EVENT_PMSUSPENDED => PMSuspended,
+    sys::VIR_DOMAIN_EVENT_CRASHED => Crashed,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventDefinedDetail {
+    Added,
+    Updated,
+    Renamed,
+    FromSnapshot,
+}
+
+pub type DomainEventDefinedDetailEnum =
+    Enum<DomainEventDefinedDetail, sys::virDomainEventDefinedDetailType>;
+
+impl_enum! {
+    enum: DomainEventDefinedDetail,
+    raw: sys::virDomainEventDefinedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_DEFINED_ADDED => Added,
+    sys::VIR_DOMAIN_EVENT_DEFINED_UPDATED => Updated,
+    sys::VIR_DOMAIN_EVENT_DEFINED_RENAMED => Renamed,
+    sys::VIR_DOMAIN_EVENT_DEFINED_FROM_SNAPSHOT => FromSnapshot,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventUndefinedDetail {
+    Removed,
+    Renamed,
+}
+
+pub type DomainEventUndefinedDetailEnum =
+    Enum<DomainEventUndefinedDetail, sys::virDomainEventUndefinedDetailType>;
+
+impl_enum! {
+    enum: DomainEventUndefinedDetail,
+    raw: sys::virDomainEventUndefinedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_UNDEFINED_REMOVED => Removed,
+    sys::VIR_DOMAIN_EVENT_UNDEFINED_RENAMED => Renamed,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventStartedDetail {
+    Booted,
+    Migrated,
+    Restored,
+    FromSnapshot,
+    Wakeup,
+}
+
+pub type DomainEventStartedDetailEnum =
+    Enum<DomainEventStartedDetail, sys::virDomainEventStartedDetailType>;
+
+impl_enum! {
+    enum: DomainEventStartedDetail,
+    raw: sys::virDomainEventStartedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_STARTED_BOOTED => Booted,
+    sys::VIR_DOMAIN_EVENT_STARTED_MIGRATED => Migrated,
+    sys::VIR_DOMAIN_EVENT_STARTED_RESTORED => Restored,
+    sys::VIR_DOMAIN_EVENT_STARTED_FROM_SNAPSHOT => FromSnapshot,
+    sys::VIR_DOMAIN_EVENT_STARTED_WAKEUP => Wakeup,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventSuspendedDetail {
+    Paused,
+    Migrated,
+    IOError,
+    Watchdog,
+    Restored,
+    FromSnapshot,
+    APIError,
+    PostCopy,
+    PostCopyFailed,
+}
+
+pub type DomainEventSuspendedDetailEnum =
+    Enum<DomainEventSuspendedDetail, sys::virDomainEventSuspendedDetailType>;
+
+impl_enum! {
+    enum: DomainEventSuspendedDetail,
+    raw: sys::virDomainEventSuspendedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_PAUSED => Paused,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_MIGRATED => Migrated,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_IOERROR => IOError,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_WATCHDOG => Watchdog,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_RESTORED => Restored,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_FROM_SNAPSHOT => FromSnapshot,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_API_ERROR => APIError,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_POSTCOPY => PostCopy,
+    sys::VIR_DOMAIN_EVENT_SUSPENDED_POSTCOPY_FAILED => PostCopyFailed,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventResumedDetail {
+    Unpaused,
+    Migrated,
+    FromSnapshot,
+    PostCopy,
+    PostCopyFailed,
+}
+
+pub type DomainEventResumedDetailEnum =
+    Enum<DomainEventResumedDetail, sys::virDomainEventResumedDetailType>;
+
+impl_enum! {
+    enum: DomainEventResumedDetail,
+    raw: sys::virDomainEventResumedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_RESUMED_UNPAUSED => Unpaused,
+    sys::VIR_DOMAIN_EVENT_RESUMED_MIGRATED => Migrated,
+    sys::VIR_DOMAIN_EVENT_RESUMED_FROM_SNAPSHOT => FromSnapshot,
+    sys::VIR_DOMAIN_EVENT_RESUMED_POSTCOPY => PostCopy,
+    sys::VIR_DOMAIN_EVENT_RESUMED_POSTCOPY_FAILED => PostCopyFailed,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventStoppedDetail {
+    Shutdown,
+    Destroyed,
+    Crashed,
+    Migrated,
+    Saved,
+    Failed,
+    FromSnapshot,
+}
+
+pub type DomainEventStoppedDetailEnum =
+    Enum<DomainEventStoppedDetail, sys::virDomainEventStoppedDetailType>;
+
+impl_enum! {
+    enum: DomainEventStoppedDetail,
+    raw: sys::virDomainEventStoppedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_STOPPED_SHUTDOWN => Shutdown,
+    sys::VIR_DOMAIN_EVENT_STOPPED_DESTROYED => Destroyed,
+    sys::VIR_DOMAIN_EVENT_STOPPED_CRASHED => Crashed,
+    sys::VIR_DOMAIN_EVENT_STOPPED_MIGRATED => Migrated,
+    sys::VIR_DOMAIN_EVENT_STOPPED_SAVED => Saved,
+    sys::VIR_DOMAIN_EVENT_STOPPED_FAILED => Failed,
+    sys::VIR_DOMAIN_EVENT_STOPPED_FROM_SNAPSHOT => FromSnapshot,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventShutdownDetail {
+    Finished,
+    Guest,
+    Host,
+}
+
+pub type DomainEventShutdownDetailEnum =
+    Enum<DomainEventShutdownDetail, sys::virDomainEventShutdownDetailType>;
+
+impl_enum! {
+    enum: DomainEventShutdownDetail,
+    raw: sys::virDomainEventShutdownDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_SHUTDOWN_FINISHED => Finished,
+    sys::VIR_DOMAIN_EVENT_SHUTDOWN_GUEST => Guest,
+    sys::VIR_DOMAIN_EVENT_SHUTDOWN_HOST => Host,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventPMSuspendedDetail {
+    Memory,
+    Disk,
+}
+
+pub type DomainEventPMSuspendedDetailEnum =
+    Enum<DomainEventPMSuspendedDetail, sys::virDomainEventPMSuspendedDetailType>;
+
+impl_enum! {
+    enum: DomainEventPMSuspendedDetail,
+    raw: sys::virDomainEventPMSuspendedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_PMSUSPENDED_MEMORY => Memory,
+    sys::VIR_DOMAIN_EVENT_PMSUSPENDED_DISK => Disk,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventCrashedDetail {
+    Panicked,
+    CrashLoaded,
+}
+
+pub type DomainEventCrashedDetailEnum =
+    Enum<DomainEventCrashedDetail, sys::virDomainEventCrashedDetailType>;
+
+impl_enum! {
+    enum: DomainEventCrashedDetail,
+    raw: sys::virDomainEventCrashedDetailType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_CRASHED_PANICKED => Panicked,
+    sys::VIR_DOMAIN_EVENT_CRASHED_CRASHLOADED => CrashLoaded,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DomainEventDetail {
+    Defined(DomainEventDefinedDetailEnum),
+    Undefined(DomainEventUndefinedDetailEnum),
+    Started(DomainEventStartedDetailEnum),
+    Suspended(DomainEventSuspendedDetailEnum),
+    Resumed(DomainEventResumedDetailEnum),
+    Stopped(DomainEventStoppedDetailEnum),
+    Shutdown(DomainEventShutdownDetailEnum),
+    PMSuspended(DomainEventPMSuspendedDetailEnum),
+    Crashed(DomainEventCrashedDetailEnum),
+}
+
+pub type DomainEventDetailEnum = Enum<DomainEventDetail, libc::c_int>;
+
+impl Display for DomainEventDetail {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            DomainEventDetail::Defined(d) => write!(f, "{}", d),
+            DomainEventDetail::Undefined(d) => write!(f, "{}", d),
+            DomainEventDetail::Started(d) => write!(f, "{}", d),
+            DomainEventDetail::Suspended(d) => write!(f, "{}", d),
+            DomainEventDetail::Resumed(d) => write!(f, "{}", d),
+            DomainEventDetail::Stopped(d) => write!(f, "{}", d),
+            DomainEventDetail::Shutdown(d) => write!(f, "{}", d),
+            DomainEventDetail::PMSuspended(d) => write!(f, "{}", d),
+            DomainEventDetail::Crashed(d) => write!(f, "{}", d),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct DomainInfo {
     /// The running state
@@ -337,683 +679,3687 @@ impl BlockStats {
     }
 }
 
-pub struct DomainStatsRecord {
-    // TODO(sahid): needs to be implemented
-    pub ptr: sys::virDomainStatsRecordPtr,
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum VcpuState {
+    Offline,
+    Running,
+    Blocked,
 }
 
-#[derive(Clone, Debug)]
-pub struct BlockInfo {
-    /// Logical size in bytes of the image (how much storage the guest
-    /// will see).
-    pub capacity: u64,
-    /// Host storage in bytes occupied by the image (such as highest
-    /// allocated extent if there are no holes, similar to 'du').
-    pub allocation: u64,
-    /// Host physical size in bytes of the image container (last
-    /// offset, similar to 'ls')
-    pub physical: u64,
-}
+pub type VcpuStateEnum = Enum<VcpuState, sys::virVcpuState>;
 
-impl BlockInfo {
-    /// # Safety
-    ///
-    /// The caller must ensure that the pointer is valid.
-    pub unsafe fn from_ptr(ptr: sys::virDomainBlockInfoPtr) -> BlockInfo {
-        BlockInfo {
-            capacity: (*ptr).capacity,
-            allocation: (*ptr).allocation,
-            physical: (*ptr).physical,
-        }
+impl_enum! {
+    enum: VcpuState,
+    raw: sys::virVcpuState,
+    match: {
+    sys::VIR_VCPU_OFFLINE => Offline,
+    sys::VIR_VCPU_RUNNING => Running,
+    sys::VIR_VCPU_BLOCKED => Blocked,
     }
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct CpuStats {
-    /// cpu usage (sum of both vcpu and hypervisor usage) in nanoseconds
-    pub cpu_time: Option<u64>,
-    /// cpu time charged to user instructions in nanoseconds
-    pub user_time: Option<u64>,
-    /// cpu time charged to system instructions in nanoseconds
-    pub system_time: Option<u64>,
-    /// vcpu usage in nanoseconds (cpu_time excluding hypervisor time)
-    pub vcpu_time: Option<u64>,
+/// Set of host CPUs, as used by libvirt to describe CPU affinity.
+///
+/// CPU `n` is stored in bit `n % 8` of byte `n / 8`. A map can be
+/// parsed from, and displayed as, a CPU list such as `"0-3,8"`.
+///
+/// # Examples
+///
+/// ```
+/// use virt::domain::CpuMap;
+///
+/// let mut map: CpuMap = "0-3,8,^2".parse().unwrap();
+/// map.set(9);
+/// assert!(map.is_set(3) && !map.is_set(2));
+/// assert_eq!("0-1,3,8-9", map.to_string());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CpuMap {
+    bytes: Vec<u8>,
 }
 
-macro_rules! cpu_stats_fields {
-    ($dir:ident, $var:ident) => {
-        vec![
-            $dir!(sys::VIR_DOMAIN_CPU_STATS_CPUTIME, UInt64, $var.cpu_time),
-            $dir!(sys::VIR_DOMAIN_CPU_STATS_USERTIME, UInt64, $var.user_time),
-            $dir!(
-                sys::VIR_DOMAIN_CPU_STATS_SYSTEMTIME,
-                UInt64,
-                $var.system_time
-            ),
-            $dir!(sys::VIR_DOMAIN_CPU_STATS_VCPUTIME, UInt64, $var.vcpu_time),
-        ]
-    };
-}
+impl CpuMap {
+    /// Creates an empty map large enough for `ncpus` CPUs.
+    pub fn new(ncpus: u32) -> CpuMap {
+        CpuMap {
+            bytes: vec![0; (ncpus as usize + 7) / 8],
+        }
+    }
 
-impl CpuStats {
-    fn from_vec(vec: Vec<sys::virTypedParameter>) -> CpuStats {
-        let mut ret = CpuStats::default();
-        let fields = cpu_stats_fields!(param_field_in, ret);
-        from_params(vec, fields);
-        ret
+    /// Creates an empty map large enough for all the CPUs of the host.
+    pub fn for_node(info: &NodeInfo) -> CpuMap {
+        CpuMap::new(info.max_cpus())
     }
-}
 
-#[derive(Clone, Debug, Default)]
-pub struct MemoryParameters {
-    /// Represents the maximum memory the guest can use.
-    pub hard_limit: Option<u64>,
-    /// Represents the memory upper limit enforced during memory
-    /// contention.
-    pub soft_limit: Option<u64>,
-    /// Represents the minimum memory guaranteed to be reserved for
-    /// the guest.
-    pub min_guarantee: Option<u64>,
-    /// Represents the maximum swap plus memory the guest can use.
-    pub swap_hard_limit: Option<u64>,
-}
+    /// Creates a map from its libvirt representation.
+    pub fn from_bytes(bytes: &[u8]) -> CpuMap {
+        CpuMap {
+            bytes: bytes.to_vec(),
+        }
+    }
 
-macro_rules! memory_parameters_fields {
-    ($dir:ident, $var:ident) => {
-        vec![
-            $dir!(sys::VIR_DOMAIN_MEMORY_HARD_LIMIT, UInt64, $var.hard_limit),
-            $dir!(sys::VIR_DOMAIN_MEMORY_SOFT_LIMIT, UInt64, $var.soft_limit),
-            $dir!(
-                sys::VIR_DOMAIN_MEMORY_MIN_GUARANTEE,
-                UInt64,
-                $var.min_guarantee
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_MEMORY_SWAP_HARD_LIMIT,
-                UInt64,
-                $var.swap_hard_limit
-            ),
-        ]
-    };
-}
+    /// Returns the libvirt representation of the map, as expected by
+    /// [`Domain::pin_vcpu`] and [`Domain::pin_emulator`].
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
 
-impl MemoryParameters {
-    pub const VALUE_UNLIMITED: u64 = sys::VIR_DOMAIN_MEMORY_PARAM_UNLIMITED;
+    /// Returns the number of CPUs the map can hold.
+    pub fn capacity(&self) -> u32 {
+        self.bytes.len() as u32 * 8
+    }
 
-    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> MemoryParameters {
-        let mut ret = MemoryParameters::default();
-        let fields = memory_parameters_fields!(param_field_in, ret);
-        from_params(vec, fields);
-        ret
+    /// Adds a CPU to the map, growing it if needed.
+    pub fn set(&mut self, cpu: u32) {
+        let byte = cpu as usize / 8;
+        if self.bytes.len() <= byte {
+            self.bytes.resize(byte + 1, 0);
+        }
+        self.bytes[byte] |= 1 << (cpu % 8);
     }
 
-    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
-        let fields = memory_parameters_fields!(param_field_out, self);
-        to_params(fields)
+    /// Removes a CPU from the map.
+    pub fn clear(&mut self, cpu: u32) {
+        if let Some(byte) = self.bytes.get_mut(cpu as usize / 8) {
+            *byte &= !(1 << (cpu % 8));
+        }
     }
-}
 
-macro_rules! numa_parameters_fields {
-    ($dir:ident, $var:ident) => {
-        vec![
-            $dir!(sys::VIR_DOMAIN_NUMA_NODESET, String, $var.node_set),
-            $dir!(sys::VIR_DOMAIN_NUMA_MODE, Int32, $var.mode),
-        ]
-    };
-}
-
-#[derive(Clone, Debug, Default)]
-pub struct NUMAParameters {
-    /// Lists the numa nodeset of a domain.
-    pub node_set: Option<String>,
-    /// Numa mode of a domain, as an int containing a
-    /// DomainNumatuneMemMode value.
-    pub mode: Option<i32>,
-}
+    /// Returns whether the map holds no CPU.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.iter().all(|&byte| byte == 0)
+    }
 
-impl NUMAParameters {
-    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> NUMAParameters {
-        let mut ret = NUMAParameters::default();
-        let fields = numa_parameters_fields!(param_field_in, ret);
-        from_params(vec, fields);
-        ret
+    /// Returns whether a CPU is in the map.
+    pub fn is_set(&self, cpu: u32) -> bool {
+        self.bytes
+            .get(cpu as usize / 8)
+            .map_or(false, |byte| byte & (1 << (cpu % 8)) != 0)
     }
 
-    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
-        let fields = numa_parameters_fields!(param_field_out, self);
-        to_params(fields)
+    /// Returns the CPUs of the map, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.capacity()).filter(move |&cpu| self.is_set(cpu))
     }
-}
 
-macro_rules! migrate_parameters_fields {
-    ($dir:ident, $var:ident) => {
-        vec![
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_AUTO_CONVERGE_INCREMENT,
-                Int32,
-                $var.auto_converge_increment
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_AUTO_CONVERGE_INITIAL,
-                Int32,
-                $var.auto_converge_initial
-            ),
-            $dir!(sys::VIR_MIGRATE_PARAM_BANDWIDTH, UInt64, $var.bandwidth),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_BANDWIDTH_POSTCOPY,
-                UInt64,
-                $var.bandwidth_postcopy
-            ),
-            $dir!(sys::VIR_MIGRATE_PARAM_COMPRESSION, String, $var.compression),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_COMPRESSION_MT_DTHREADS,
-                Int32,
-                $var.compression_mt_dthreads
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_COMPRESSION_MT_LEVEL,
-                Int32,
-                $var.compression_mt_level
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_COMPRESSION_MT_THREADS,
-                Int32,
-                $var.compression_mt_threads
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_COMPRESSION_XBZRLE_CACHE,
-                UInt64,
-                $var.compression_xbzrle_cache
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_COMPRESSION_ZLIB_LEVEL,
-                Int32,
-                $var.compression_zlib_level
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_COMPRESSION_ZSTD_LEVEL,
-                Int32,
-                $var.compression_zstd_level
-            ),
-            $dir!(sys::VIR_MIGRATE_PARAM_DEST_NAME, String, $var.dest_name),
-            $dir!(sys::VIR_MIGRATE_PARAM_DEST_XML, String, $var.dest_xml),
-            $dir!(sys::VIR_MIGRATE_PARAM_DISKS_PORT, Int32, $var.disks_port),
-            $dir!(sys::VIR_MIGRATE_PARAM_DISKS_URI, String, $var.disks_uri),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_GRAPHICS_URI,
-                String,
-                $var.graphics_uri
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_LISTEN_ADDRESS,
-                String,
-                $var.listen_address
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_MIGRATE_DISKS,
-                VecString,
-                $var.migrate_disks
-            ),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_PARALLEL_CONNECTIONS,
-                Int32,
-                $var.parallel_connections
-            ),
-            $dir!(sys::VIR_MIGRATE_PARAM_PERSIST_XML, String, $var.persist_xml),
-            $dir!(
-                sys::VIR_MIGRATE_PARAM_TLS_DESTINATION,
-                String,
-                $var.tls_destination
-            ),
-            $dir!(sys::VIR_MIGRATE_PARAM_URI, String, $var.uri),
-        ]
-    };
+    // Adds the CPUs from `first` to `last` included, a byte at a time.
+    fn set_range(&mut self, first: u32, last: u32) {
+        let (first_byte, last_byte) = (first as usize / 8, last as usize / 8);
+        if self.bytes.len() <= last_byte {
+            self.bytes.resize(last_byte + 1, 0);
+        }
+        for (i, byte) in (first_byte..).zip(&mut self.bytes[first_byte..=last_byte]) {
+            let low = if i == first_byte { first % 8 } else { 0 };
+            let high = if i == last_byte { last % 8 } else { 7 };
+            *byte |= (0xff >> (7 - high)) & (0xff << low);
+        }
+    }
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct MigrateParameters {
-    pub auto_converge_increment: Option<i32>,
-    pub auto_converge_initial: Option<i32>,
-    pub bandwidth: Option<u64>,
-    pub bandwidth_postcopy: Option<u64>,
-    pub compression: Option<String>,
-    pub compression_mt_dthreads: Option<i32>,
-    pub compression_mt_level: Option<i32>,
-    pub compression_mt_threads: Option<i32>,
-    pub compression_xbzrle_cache: Option<u64>,
-    pub compression_zlib_level: Option<i32>,
-    pub compression_zstd_level: Option<i32>,
-    pub dest_name: Option<String>,
-    pub dest_xml: Option<String>,
-    pub disks_port: Option<i32>,
-    pub disks_uri: Option<String>,
-    pub graphics_uri: Option<String>,
-    pub listen_address: Option<String>,
-    pub migrate_disks: Vec<String>,
-    pub parallel_connections: Option<i32>,
-    pub persist_xml: Option<String>,
-    pub tls_destination: Option<String>,
-    pub uri: Option<String>,
+/// Number of CPUs a parsed CPU list may refer to, which bounds the
+/// size of the resulting map.
+const CPU_LIST_MAX_CPUS: u32 = 4096;
+
+impl FromStr for CpuMap {
+    type Err = Error;
+
+    /// Parses a comma separated list of CPUs and CPU ranges, where
+    /// `^n` excludes a CPU added earlier. CPUs are numbered below
+    /// 4096.
+    fn from_str(s: &str) -> Result<CpuMap, Error> {
+        let invalid = || Error::new(ErrorNumber::InvalidArg, format!("invalid CPU list '{s}'"));
+        let parse = |cpu: &str| match cpu.trim().parse::<u32>() {
+            Ok(cpu) if cpu < CPU_LIST_MAX_CPUS => Ok(cpu),
+            _ => Err(invalid()),
+        };
+        let mut map = CpuMap::default();
+        for item in s.split(',').filter(|item| !item.trim().is_empty()) {
+            if let Some(cpu) = item.trim().strip_prefix('^') {
+                map.clear(parse(cpu)?);
+            } else if let Some((first, last)) = item.split_once('-') {
+                let (first, last) = (parse(first)?, parse(last)?);
+                if first > last {
+                    return Err(invalid());
+                }
+                map.set_range(first, last);
+            } else {
+                map.set(parse(item)?);
+            }
+        }
+        Ok(map)
+    }
 }
 
-impl MigrateParameters {
-    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> MigrateParameters {
-        let mut ret = MigrateParameters::default();
-        let fields = migrate_parameters_fields!(param_field_in, ret);
-        from_params(vec, fields);
-        ret
+impl Display for CpuMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut cpus = self.iter().peekable();
+        let mut first = true;
+        while let Some(start) = cpus.next() {
+            let mut end = start;
+            while cpus.peek() == Some(&(end + 1)) {
+                end = cpus.next().unwrap();
+            }
+            if !first {
+                f.write_str(",")?;
+            }
+            first = false;
+            if start == end {
+                write!(f, "{start}")?;
+            } else {
+                write!(f, "{start}-{end}")?;
+            }
+        }
+        Ok(())
     }
+}
 
-    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
-        let fields = migrate_parameters_fields!(param_field_out, self);
-        to_params(fields)
-    }
+// Splits the cpumaps buffer filled by libvirt into one map per
+// element.
+fn split_cpumaps(cpumaps: &[u8], maplen: usize, count: usize) -> Vec<CpuMap> {
+    cpumaps
+        .chunks(maplen)
+        .take(count)
+        .map(CpuMap::from_bytes)
+        .collect()
 }
 
+/// Information about a virtual CPU, see [`Domain::vcpus`].
 #[derive(Clone, Debug)]
-pub struct IPAddress {
-    pub typed: i64,
-    pub addr: String,
-    pub prefix: u64,
+pub struct VcpuInfo {
+    /// Virtual CPU number.
+    pub number: u32,
+    /// State of the virtual CPU.
+    pub state: VcpuStateEnum,
+    /// CPU time used, in nanoseconds.
+    pub cpu_time: u64,
+    /// Host CPU the virtual CPU is running on, -1 if it is offline.
+    pub cpu: i32,
+    /// Host CPUs the virtual CPU can run on.
+    pub affinity: CpuMap,
 }
 
-impl IPAddress {
-    /// # Safety
-    ///
-    /// The caller must ensure that the pointer is valid.
-    pub unsafe fn from_ptr(ptr: sys::virDomainIPAddressPtr) -> IPAddress {
-        IPAddress {
-            typed: (*ptr).type_ as i64,
-            addr: c_chars_to_string!((*ptr).addr),
-            prefix: (*ptr).prefix as u64,
+/// vCPUs as seen by the guest agent, see [`Domain::guest_vcpus`].
+#[derive(Clone, Debug, Default)]
+pub struct GuestVcpus {
+    /// vCPUs known to the guest.
+    pub vcpus: CpuMap,
+    /// vCPUs online in the guest.
+    pub online: CpuMap,
+    /// vCPUs the guest is able to offline.
+    pub offlinable: CpuMap,
+}
+
+impl GuestVcpus {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> Result<GuestVcpus, Error> {
+        let mut ret = GuestVcpus::default();
+        for (key, value) in from_params_generic(&vec) {
+            let map = match key.as_str() {
+                "vcpus" => &mut ret.vcpus,
+                "online" => &mut ret.online,
+                "offlinable" => &mut ret.offlinable,
+                _ => continue,
+            };
+            if let Some(cpus) = value.as_str() {
+                *map = cpus.parse()?;
+            }
         }
+        Ok(ret)
     }
 }
 
+/// IOThread of a domain, see [`Domain::iothread_info`].
 #[derive(Clone, Debug)]
-pub struct Interface {
-    pub name: String,
-    pub hwaddr: String,
-    pub naddrs: u64,
-    pub addrs: Vec<IPAddress>,
+pub struct IOThreadInfo {
+    /// IOThread identifier.
+    pub iothread_id: u32,
+    /// Host CPUs the IOThread can run on.
+    pub cpumap: CpuMap,
 }
 
-impl Interface {
+impl IOThreadInfo {
     /// # Safety
     ///
     /// The caller must ensure that the pointer is valid.
-    pub unsafe fn from_ptr(ptr: sys::virDomainInterfacePtr) -> Interface {
-        let naddrs = (*ptr).naddrs;
-        let mut addrs = vec![];
-        for x in 0..naddrs as isize {
-            addrs.push(IPAddress::from_ptr((*ptr).addrs.offset(x)));
-        }
-        Interface {
-            name: c_chars_to_string!((*ptr).name),
-            hwaddr: c_chars_to_string!((*ptr).hwaddr),
-            naddrs: naddrs as u64,
-            addrs,
+    pub unsafe fn from_ptr(ptr: sys::virDomainIOThreadInfoPtr) -> IOThreadInfo {
+        let cpumap = if (*ptr).cpumap.is_null() {
+            CpuMap::default()
+        } else {
+            CpuMap::from_bytes(slice::from_raw_parts(
+                (*ptr).cpumap,
+                (*ptr).cpumaplen as usize,
+            ))
+        };
+        IOThreadInfo {
+            iothread_id: (*ptr).iothread_id,
+            cpumap,
         }
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct InterfaceStats {
-    pub rx_bytes: i64,
-    pub rx_packets: i64,
-    pub rx_errs: i64,
-    pub rx_drop: i64,
-    pub tx_bytes: i64,
-    pub tx_packets: i64,
-    pub tx_errs: i64,
-    pub tx_drop: i64,
+// Pairs a domain state with its reason, whose type depends on the
+// state.
+fn state_from_raw(
+    state: libc::c_int,
+    reason: libc::c_int,
+) -> (DomainStateEnum, DomainStateReasonEnum) {
+    let state = DomainStateEnum::from_raw(state as sys::virDomainState);
+    let reason = match state {
+        Enum::Known(k) => DomainStateReasonEnum::Known(match k {
+            DomainState::NoState => DomainStateReason::NoState(DomainNoStateReasonEnum::from_raw(
+                reason as sys::virDomainNostateReason,
+            )),
+            DomainState::Running => DomainStateReason::Running(DomainRunningReasonEnum::from_raw(
+                reason as sys::virDomainRunningReason,
+            )),
+            DomainState::Blocked => DomainStateReason::Blocked(DomainBlockedReasonEnum::from_raw(
+                reason as sys::virDomainBlockedReason,
+            )),
+            DomainState::Paused => DomainStateReason::Paused(DomainPausedReasonEnum::from_raw(
+                reason as sys::virDomainPausedReason,
+            )),
+            DomainState::Shutdown => DomainStateReason::Shutdown(
+                DomainShutdownReasonEnum::from_raw(reason as sys::virDomainShutdownReason),
+            ),
+            DomainState::Shutoff => DomainStateReason::Shutoff(DomainShutoffReasonEnum::from_raw(
+                reason as sys::virDomainShutoffReason,
+            )),
+            DomainState::Crashed => DomainStateReason::Crashed(DomainCrashedReasonEnum::from_raw(
+                reason as sys::virDomainCrashedReason,
+            )),
+            DomainState::PMSuspended => DomainStateReason::PMSuspended(
+                DomainPMSuspendedReasonEnum::from_raw(reason as sys::virDomainPMSuspendedReason),
+            ),
+        }),
+        Enum::Unknown(_) => DomainStateReasonEnum::Unknown(reason),
+    };
+    (state, reason)
 }
 
-impl InterfaceStats {
-    /// # Safety
-    ///
-    /// The caller must ensure that the pointer is valid.
-    pub unsafe fn from_ptr(ptr: sys::virDomainInterfaceStatsPtr) -> InterfaceStats {
-        InterfaceStats {
-            rx_bytes: (*ptr).rx_bytes,
-            rx_packets: (*ptr).rx_packets,
-            rx_errs: (*ptr).rx_errs,
-            rx_drop: (*ptr).rx_drop,
-            tx_bytes: (*ptr).tx_bytes,
-            tx_packets: (*ptr).tx_packets,
-            tx_errs: (*ptr).tx_errs,
-            tx_drop: (*ptr).tx_drop,
-        }
-    }
+/// Statistics of the `cpu-total` group, in nanoseconds.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsCpu {
+    /// Total cpu time spent for this domain.
+    pub time: Option<u64>,
+    /// User cpu time spent.
+    pub user: Option<u64>,
+    /// System cpu time spent.
+    pub system: Option<u64>,
+    /// Time spent polling successfully in halt polling.
+    pub haltpoll_success_time: Option<u64>,
+    /// Time spent polling unsuccessfully in halt polling.
+    pub haltpoll_fail_time: Option<u64>,
 }
 
-#[derive(Clone, Debug)]
-pub struct MemoryStat {
-    pub tag: u32,
-    pub val: u64,
+/// Statistics of the `balloon` group, memory sizes are in KiB.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsBalloon {
+    /// Current balloon value.
+    pub current: Option<u64>,
+    /// Maximum balloon value.
+    pub maximum: Option<u64>,
+    /// Amount of data read from swap space.
+    pub swap_in: Option<u64>,
+    /// Amount of memory written out to swap space.
+    pub swap_out: Option<u64>,
+    /// Number of page faults requiring disk IO.
+    pub major_fault: Option<u64>,
+    /// Number of other page faults.
+    pub minor_fault: Option<u64>,
+    /// Amount of memory left unused by the system.
+    pub unused: Option<u64>,
+    /// Amount of usable memory as seen by the domain.
+    pub available: Option<u64>,
+    /// Resident Set Size of the running domain's process.
+    pub rss: Option<u64>,
+    /// Amount of memory which can be reclaimed by balloon without
+    /// causing host swapping.
+    pub usable: Option<u64>,
+    /// Timestamp of the last statistic update, in seconds.
+    pub last_update: Option<u64>,
+    /// Amount of memory that can be reclaimed without additional IO.
+    pub disk_caches: Option<u64>,
+    /// Number of successful huge page allocations.
+    pub hugetlb_pgalloc: Option<u64>,
+    /// Number of failed huge page allocations.
+    pub hugetlb_pgfail: Option<u64>,
 }
 
-impl MemoryStat {
-    /// # Safety
-    ///
-    /// The caller must ensure that the pointer is valid.
-    pub unsafe fn from_ptr(ptr: *const sys::virDomainMemoryStatStruct) -> MemoryStat {
-        MemoryStat {
-            tag: (*ptr).tag as u32,
-            val: (*ptr).val,
-        }
-    }
+/// Statistics of a virtual CPU from the `vcpu` group.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsVcpu {
+    /// State of the virtual CPU.
+    pub state: Option<VcpuStateEnum>,
+    /// Virtual cpu time spent, in nanoseconds.
+    pub time: Option<u64>,
+    /// Time the vCPU wants to run but the host scheduler has
+    /// something else running ahead of it, in nanoseconds.
+    pub wait: Option<u64>,
+    /// Whether the virtual CPU is halted.
+    pub halted: Option<bool>,
+    /// Time the vCPU spent waiting in the queue instead of running,
+    /// in nanoseconds.
+    pub delay: Option<u64>,
 }
 
-/// Information about the progress of a background job that is
-/// affecting a domain.
+/// Statistics of a network interface from the `interface` group.
 #[derive(Clone, Debug, Default)]
-pub struct JobStats {
-    pub r#type: i32,
+pub struct DomainStatsInterface {
+    /// Name of the host interface.
+    pub name: Option<String>,
+    pub rx_bytes: Option<u64>,
+    pub rx_pkts: Option<u64>,
+    pub rx_errs: Option<u64>,
+    pub rx_drop: Option<u64>,
+    pub tx_bytes: Option<u64>,
+    pub tx_pkts: Option<u64>,
+    pub tx_errs: Option<u64>,
+    pub tx_drop: Option<u64>,
+}
 
-    pub auto_converge_throttle: Option<i32>,
+/// Statistics of a block device, or of an image of its backing
+/// chain, from the `block` group.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsBlock {
+    /// Name of the block device, shared by all images of its backing
+    /// chain.
+    pub name: Option<String>,
+    /// Index of the image in the backing chain, only reported with
+    /// `sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_BACKING`.
+    pub backing_index: Option<u32>,
+    /// Source of the block device, if it is a file or block device.
+    pub path: Option<String>,
+    pub rd_reqs: Option<u64>,
+    pub rd_bytes: Option<u64>,
+    /// Total time spent on reads, in nanoseconds.
+    pub rd_times: Option<u64>,
+    pub wr_reqs: Option<u64>,
+    pub wr_bytes: Option<u64>,
+    /// Total time spent on writes, in nanoseconds.
+    pub wr_times: Option<u64>,
+    pub fl_reqs: Option<u64>,
+    /// Total time spent on cache flushes, in nanoseconds.
+    pub fl_times: Option<u64>,
+    pub errors: Option<u64>,
+    /// Offset of the highest written sector, in bytes.
+    pub allocation: Option<u64>,
+    /// Logical size of the block device, in bytes.
+    pub capacity: Option<u64>,
+    /// Physical size of the container of the backing image, in bytes.
+    pub physical: Option<u64>,
+    /// Write threshold of the block device, in bytes.
+    pub threshold: Option<u64>,
+}
 
-    pub compression_bytes: Option<u64>,
-    pub compression_cache: Option<u64>,
-    pub compression_cache_misses: Option<u64>,
-    pub compression_overflow: Option<u64>,
-    pub compression_pages: Option<u64>,
+/// Counters of the `perf` group, for the enabled perf events.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsPerf {
+    pub cmt: Option<u64>,
+    pub mbmt: Option<u64>,
+    pub mbml: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub cache_references: Option<u64>,
+    pub instructions: Option<u64>,
+    pub cpu_cycles: Option<u64>,
+    pub branch_instructions: Option<u64>,
+    pub branch_misses: Option<u64>,
+    pub bus_cycles: Option<u64>,
+    pub stalled_cycles_frontend: Option<u64>,
+    pub stalled_cycles_backend: Option<u64>,
+    pub ref_cpu_cycles: Option<u64>,
+    pub cpu_clock: Option<u64>,
+    pub task_clock: Option<u64>,
+    pub page_faults: Option<u64>,
+    pub context_switches: Option<u64>,
+    pub cpu_migrations: Option<u64>,
+    pub page_faults_min: Option<u64>,
+    pub page_faults_maj: Option<u64>,
+    pub alignment_faults: Option<u64>,
+    pub emulation_faults: Option<u64>,
+}
 
-    pub data_processed: Option<u64>,
-    pub data_remaining: Option<u64>,
-    pub data_total: Option<u64>,
+/// Statistics of an IOThread from the `iothread` group.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsIOThread {
+    /// IOThread identifier.
+    pub id: u32,
+    /// Maximum polling time in nanoseconds, 0 when polling is
+    /// disabled.
+    pub poll_max_ns: Option<u64>,
+    /// Polling time growth factor.
+    pub poll_grow: Option<u64>,
+    /// Polling time shrink factor.
+    pub poll_shrink: Option<u64>,
+}
 
-    pub disk_bps: Option<u64>,
-    pub disk_processed: Option<u64>,
-    pub disk_remaining: Option<u64>,
-    pub disk_temp_total: Option<u64>,
-    pub disk_temp_used: Option<u64>,
-    pub disk_total: Option<u64>,
+/// Memory bandwidth used on a host NUMA node.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsMemoryBandwidthNode {
+    /// Host NUMA node identifier.
+    pub id: Option<u32>,
+    /// Bytes consumed by the vCPUs on this node's memory controller.
+    pub bytes_local: Option<u64>,
+    /// Bytes consumed by the vCPUs from all memory controllers.
+    pub bytes_total: Option<u64>,
+}
 
-    pub downtime: Option<u64>,
-    pub downtime_net: Option<u64>,
+/// Memory bandwidth monitor from the `memory` group.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsMemoryBandwidthMonitor {
+    /// Name of the monitor.
+    pub name: Option<String>,
+    /// vCPUs of the monitor, e.g. `"0-3"`.
+    pub vcpus: Option<String>,
+    pub nodes: Vec<DomainStatsMemoryBandwidthNode>,
+}
 
-    pub error_message: Option<String>,
+/// Statistics of the `dirtyrate` group.
+#[derive(Clone, Debug, Default)]
+pub struct DomainStatsDirtyRate {
+    /// Status of the last calculation, see
+    /// `sys::virDomainDirtyRateStatus`.
+    pub calc_status: Option<i32>,
+    /// Start time of the last calculation, in seconds.
+    pub calc_start_time: Option<i64>,
+    /// Period of the last calculation, in seconds.
+    pub calc_period: Option<i32>,
+    /// Calculated memory dirty rate, in MiB/s.
+    pub megabytes_per_second: Option<i64>,
+    /// Mode of the last calculation: `"page-sampling"`,
+    /// `"dirty-bitmap"` or `"dirty-ring"`.
+    pub calc_mode: Option<String>,
+    /// Dirty rate of each vCPU in MiB/s, indexed by vCPU number, in
+    /// `"dirty-ring"` mode.
+    pub vcpus: Vec<Option<i64>>,
+}
 
-    pub mem_bps: Option<u64>,
-    pub mem_constant: Option<u64>,
-    pub mem_dirty_rate: Option<u64>,
-    pub mem_iteration: Option<u64>,
-    pub mem_normal: Option<u64>,
-    pub mem_normal_bytes: Option<u64>,
-    pub mem_page_size: Option<u64>,
-    pub mem_postcopy_reqs: Option<u64>,
-    pub mem_processed: Option<u64>,
-    pub mem_remaining: Option<u64>,
-    pub mem_total: Option<u64>,
+impl_flags! {
+    /// Groups of statistics requested from
+    /// [`crate::connect::Connect::all_domain_stats`] and
+    /// [`Domain::list_get_stats`].
+    struct DomainStatsTypes: sys::virDomainStatsTypes {
+        /// State and reason of the domain.
+        const STATE = sys::VIR_DOMAIN_STATS_STATE;
+        /// CPU usage.
+        const CPU_TOTAL = sys::VIR_DOMAIN_STATS_CPU_TOTAL;
+        /// Memory balloon.
+        const BALLOON = sys::VIR_DOMAIN_STATS_BALLOON;
+        /// Virtual CPUs.
+        const VCPU = sys::VIR_DOMAIN_STATS_VCPU;
+        /// Network interfaces.
+        const INTERFACE = sys::VIR_DOMAIN_STATS_INTERFACE;
+        /// Block devices.
+        const BLOCK = sys::VIR_DOMAIN_STATS_BLOCK;
+        /// Perf event counters.
+        const PERF = sys::VIR_DOMAIN_STATS_PERF;
+        /// IOThreads.
+        const IOTHREAD = sys::VIR_DOMAIN_STATS_IOTHREAD;
+        /// Memory bandwidth.
+        const MEMORY = sys::VIR_DOMAIN_STATS_MEMORY;
+        /// Memory dirty rate.
+        const DIRTYRATE = sys::VIR_DOMAIN_STATS_DIRTYRATE;
+        /// Hypervisor specific statistics of the VM.
+        const VM = sys::VIR_DOMAIN_STATS_VM;
+    }
+}
 
-    pub operation: Option<i32>,
+impl_flags! {
+    /// Flags for [`crate::connect::Connect::all_domain_stats`] and
+    /// [`Domain::list_get_stats`].
+    struct DomainStatsFlags: sys::virConnectGetAllDomainStatsFlags {
+        /// Only active domains.
+        const ACTIVE = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_ACTIVE;
+        /// Only inactive domains.
+        const INACTIVE = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_INACTIVE;
+        /// Only persistent domains.
+        const PERSISTENT = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_PERSISTENT;
+        /// Only transient domains.
+        const TRANSIENT = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_TRANSIENT;
+        /// Only running domains.
+        const RUNNING = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_RUNNING;
+        /// Only paused domains.
+        const PAUSED = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_PAUSED;
+        /// Only shut off domains.
+        const SHUTOFF = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_SHUTOFF;
+        /// Only domains in another state.
+        const OTHER = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_OTHER;
+        /// Report no statistics rather than wait for a busy domain.
+        const NOWAIT = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_NOWAIT;
+        /// Include statistics of the backing chain of block devices.
+        const BACKING = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_BACKING;
+        /// Fail when a requested group is not supported.
+        const ENFORCE_STATS = sys::VIR_CONNECT_GET_ALL_DOMAINS_STATS_ENFORCE_STATS;
+    }
+}
 
-    pub setup_time: Option<u64>,
+/// Statistics of a domain, as returned by
+/// [`crate::connect::Connect::all_domain_stats`] and
+/// [`Domain::list_get_stats`].
+///
+/// Only the groups that were requested and are supported by the
+/// hypervisor are filled. Parameters the bindings do not know about
+/// are kept in `other`.
+#[derive(Debug)]
+pub struct DomainStatsRecord {
+    /// Domain the statistics belong to.
+    pub domain: Domain,
+    /// State and reason of the domain, from the `state` group.
+    pub state: Option<(DomainStateEnum, DomainStateReasonEnum)>,
+    pub cpu: Option<DomainStatsCpu>,
+    pub balloon: Option<DomainStatsBalloon>,
+    /// Number of online vCPUs.
+    pub vcpu_current: Option<u32>,
+    /// Maximum number of online vCPUs.
+    pub vcpu_maximum: Option<u32>,
+    /// Statistics of each vCPU, indexed by vCPU number.
+    pub vcpus: Vec<DomainStatsVcpu>,
+    pub interfaces: Vec<DomainStatsInterface>,
+    /// Block devices, followed by the images of their backing chain
+    /// when requested.
+    pub blocks: Vec<DomainStatsBlock>,
+    pub perf: Option<DomainStatsPerf>,
+    pub iothreads: Vec<DomainStatsIOThread>,
+    pub memory_bandwidth: Vec<DomainStatsMemoryBandwidthMonitor>,
+    pub dirty_rate: Option<DomainStatsDirtyRate>,
+    /// Remaining parameters, keyed by their full name.
+    pub other: HashMap<String, TypedParamValue>,
+}
 
-    pub success: Option<bool>,
+// Returns the element at index, growing the vector as needed.
+fn nth_mut<T: Default>(v: &mut Vec<T>, index: usize) -> &mut T {
+    if v.len() <= index {
+        v.resize_with(index + 1, T::default);
+    }
+    &mut v[index]
+}
 
-    pub time_elapsed: Option<u64>,
-    pub time_elapsed_net: Option<u64>,
-    pub time_remaining: Option<u64>,
+// Splits "<index>.<field>" keys of per device parameters.
+fn split_index(key: &str) -> Option<(usize, &str)> {
+    let (index, field) = key.split_once('.')?;
+    Some((index.parse().ok()?, field))
 }
 
-macro_rules! job_stats_fields {
-    ($dir:ident, $var:ident) => {
-        vec![
-            $dir!(
-                sys::VIR_DOMAIN_JOB_AUTO_CONVERGE_THROTTLE,
-                Int32,
-                $var.auto_converge_throttle
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_COMPRESSION_BYTES,
-                UInt64,
-                $var.compression_bytes
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_COMPRESSION_CACHE,
-                UInt64,
-                $var.compression_cache
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_COMPRESSION_CACHE_MISSES,
-                UInt64,
-                $var.compression_cache_misses
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_COMPRESSION_OVERFLOW,
-                UInt64,
-                $var.compression_overflow
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_COMPRESSION_PAGES,
-                UInt64,
-                $var.compression_pages
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_DATA_PROCESSED,
-                UInt64,
-                $var.data_processed
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_DATA_REMAINING,
-                UInt64,
-                $var.data_remaining
-            ),
-            $dir!(sys::VIR_DOMAIN_JOB_DATA_TOTAL, UInt64, $var.data_total),
-            $dir!(sys::VIR_DOMAIN_JOB_DISK_BPS, UInt64, $var.disk_bps),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_DISK_PROCESSED,
-                UInt64,
-                $var.disk_processed
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_DISK_REMAINING,
-                UInt64,
-                $var.disk_remaining
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_DISK_TEMP_TOTAL,
-                UInt64,
-                $var.disk_temp_total
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_DISK_TEMP_USED,
-                UInt64,
-                $var.disk_temp_used
-            ),
-            $dir!(sys::VIR_DOMAIN_JOB_DISK_TOTAL, UInt64, $var.disk_total),
-            $dir!(sys::VIR_DOMAIN_JOB_DOWNTIME, UInt64, $var.downtime),
-            $dir!(sys::VIR_DOMAIN_JOB_DOWNTIME_NET, UInt64, $var.downtime_net),
-            $dir!(sys::VIR_DOMAIN_JOB_ERRMSG, String, $var.error_message),
-            $dir!(sys::VIR_DOMAIN_JOB_MEMORY_BPS, UInt64, $var.mem_bps),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_CONSTANT,
-                UInt64,
-                $var.mem_constant
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_DIRTY_RATE,
-                UInt64,
-                $var.mem_dirty_rate
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_ITERATION,
-                UInt64,
-                $var.mem_iteration
-            ),
-            $dir!(sys::VIR_DOMAIN_JOB_MEMORY_NORMAL, UInt64, $var.mem_normal),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_NORMAL_BYTES,
-                UInt64,
-                $var.mem_normal_bytes
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_PAGE_SIZE,
-                UInt64,
-                $var.mem_page_size
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_POSTCOPY_REQS,
-                UInt64,
-                $var.mem_postcopy_reqs
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_PROCESSED,
-                UInt64,
-                $var.mem_processed
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_MEMORY_REMAINING,
-                UInt64,
-                $var.mem_remaining
-            ),
-            $dir!(sys::VIR_DOMAIN_JOB_MEMORY_TOTAL, UInt64, $var.mem_total),
-            $dir!(sys::VIR_DOMAIN_JOB_OPERATION, Int32, $var.operation),
-            $dir!(sys::VIR_DOMAIN_JOB_SETUP_TIME, UInt64, $var.setup_time),
-            $dir!(sys::VIR_DOMAIN_JOB_SUCCESS, Bool, $var.success),
-            $dir!(sys::VIR_DOMAIN_JOB_TIME_ELAPSED, UInt64, $var.time_elapsed),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_TIME_ELAPSED_NET,
-                UInt64,
-                $var.time_elapsed_net
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_JOB_TIME_REMAINING,
-                UInt64,
-                $var.time_remaining
-            ),
-        ]
-    };
+fn to_u32(v: &TypedParamValue) -> Option<u32> {
+    v.as_u64().and_then(|v| u32::try_from(v).ok())
 }
 
-impl From<(i32, Vec<sys::virTypedParameter>)> for JobStats {
-    fn from((r#type, params): (i32, Vec<sys::virTypedParameter>)) -> Self {
-        let mut stats = Self {
-            r#type,
-            ..Default::default()
+fn to_i32(v: &TypedParamValue) -> Option<i32> {
+    v.as_i64().and_then(|v| i32::try_from(v).ok())
+}
+
+fn to_string(v: &TypedParamValue) -> Option<String> {
+    v.as_str().map(String::from)
+}
+
+impl DomainStatsRecord {
+    /// Parses the records of a list returned by libvirt, then frees
+    /// the list.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid and that the
+    /// list holds `size` records.
+    pub(crate) unsafe fn from_list(
+        list: *mut sys::virDomainStatsRecordPtr,
+        size: usize,
+    ) -> Vec<DomainStatsRecord> {
+        let records = slice::from_raw_parts(list, size)
+            .iter()
+            .map(|&record| DomainStatsRecord::from_ptr(record))
+            .collect();
+        sys::virDomainStatsRecordListFree(list);
+        records
+    }
+
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid. The record
+    /// itself is not freed.
+    pub unsafe fn from_ptr(ptr: sys::virDomainStatsRecordPtr) -> DomainStatsRecord {
+        // The record keeps its own reference on the domain.
+        sys::virDomainRef((*ptr).dom);
+        let mut record = DomainStatsRecord {
+            domain: Domain::from_ptr((*ptr).dom),
+            state: None,
+            cpu: None,
+            balloon: None,
+            vcpu_current: None,
+            vcpu_maximum: None,
+            vcpus: Vec::new(),
+            interfaces: Vec::new(),
+            blocks: Vec::new(),
+            perf: None,
+            iothreads: Vec::new(),
+            memory_bandwidth: Vec::new(),
+            dirty_rate: None,
+            other: HashMap::new(),
+        };
+        let params = if (*ptr).params.is_null() {
+            &[]
+        } else {
+            slice::from_raw_parts((*ptr).params, (*ptr).nparams as usize)
+        };
+        let (mut state, mut reason) = (None, None);
+        for (key, value) in from_params_generic(params) {
+            let known = match key.split_once('.') {
+                Some(("state", "state")) => {
+                    state = value.as_i64();
+                    true
+                }
+                Some(("state", "reason")) => {
+                    reason = value.as_i64();
+                    true
+                }
+                Some(("cpu", field)) => record.parse_cpu(field, &value),
+                Some(("balloon", field)) => record.parse_balloon(field, &value),
+                Some(("vcpu", field)) => record.parse_vcpu(field, &value),
+                Some(("net", field)) => record.parse_net(field, &value),
+                Some(("block", field)) => record.parse_block(field, &value),
+                Some(("perf", field)) => record.parse_perf(field, &value),
+                Some(("iothread", field)) => record.parse_iothread(field, &value),
+                Some(("memory", field)) => match field.strip_prefix("bandwidth.monitor.") {
+                    Some(field) => record.parse_memory_bandwidth(field, &value),
+                    None => false,
+                },
+                Some(("dirtyrate", field)) => record.parse_dirty_rate(field, &value),
+                _ => false,
+            };
+            if !known {
+                record.other.insert(key, value);
+            }
+        }
+        if let (Some(state), Some(reason)) = (state, reason) {
+            record.state = Some(state_from_raw(state as libc::c_int, reason as libc::c_int));
+        }
+        record
+    }
+
+    /// Returns the images of the backing chain of the block device
+    /// `name`, starting with the active one.
+    pub fn backing_chain(&self, name: &str) -> Vec<&DomainStatsBlock> {
+        self.blocks
+            .iter()
+            .filter(|b| b.name.as_deref() == Some(name))
+            .collect()
+    }
+
+    fn parse_cpu(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        let cpu = self.cpu.get_or_insert_with(Default::default);
+        let slot = match field {
+            "time" => &mut cpu.time,
+            "user" => &mut cpu.user,
+            "system" => &mut cpu.system,
+            "haltpoll.success.time" => &mut cpu.haltpoll_success_time,
+            "haltpoll.fail.time" => &mut cpu.haltpoll_fail_time,
+            _ => return false,
+        };
+        *slot = value.as_u64();
+        true
+    }
+
+    fn parse_balloon(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        let balloon = self.balloon.get_or_insert_with(Default::default);
+        let slot = match field {
+            "current" => &mut balloon.current,
+            "maximum" => &mut balloon.maximum,
+            "swap_in" => &mut balloon.swap_in,
+            "swap_out" => &mut balloon.swap_out,
+            "major_fault" => &mut balloon.major_fault,
+            "minor_fault" => &mut balloon.minor_fault,
+            "unused" => &mut balloon.unused,
+            "available" => &mut balloon.available,
+            "rss" => &mut balloon.rss,
+            "usable" => &mut balloon.usable,
+            "last-update" => &mut balloon.last_update,
+            "disk_caches" => &mut balloon.disk_caches,
+            "hugetlb_pgalloc" => &mut balloon.hugetlb_pgalloc,
+            "hugetlb_pgfail" => &mut balloon.hugetlb_pgfail,
+            _ => return false,
         };
+        *slot = value.as_u64();
+        true
+    }
+
+    fn parse_vcpu(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        match field {
+            "current" => self.vcpu_current = to_u32(value),
+            "maximum" => self.vcpu_maximum = to_u32(value),
+            _ => {
+                let (index, field) = match split_index(field) {
+                    Some(v) => v,
+                    None => return false,
+                };
+                let vcpu = nth_mut(&mut self.vcpus, index);
+                match field {
+                    "state" => {
+                        vcpu.state = value
+                            .as_i64()
+                            .map(|v| VcpuStateEnum::from_raw(v as sys::virVcpuState))
+                    }
+                    "time" => vcpu.time = value.as_u64(),
+                    "wait" => vcpu.wait = value.as_u64(),
+                    "halted" => vcpu.halted = value.as_bool(),
+                    "delay" => vcpu.delay = value.as_u64(),
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
 
-        let fields = job_stats_fields!(param_field_in, stats);
+    fn parse_net(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        if field == "count" {
+            return true;
+        }
+        let (index, field) = match split_index(field) {
+            Some(v) => v,
+            None => return false,
+        };
+        let net = nth_mut(&mut self.interfaces, index);
+        let slot = match field {
+            "name" => {
+                net.name = to_string(value);
+                return true;
+            }
+            "rx.bytes" => &mut net.rx_bytes,
+            "rx.pkts" => &mut net.rx_pkts,
+            "rx.errs" => &mut net.rx_errs,
+            "rx.drop" => &mut net.rx_drop,
+            "tx.bytes" => &mut net.tx_bytes,
+            "tx.pkts" => &mut net.tx_pkts,
+            "tx.errs" => &mut net.tx_errs,
+            "tx.drop" => &mut net.tx_drop,
+            _ => return false,
+        };
+        *slot = value.as_u64();
+        true
+    }
 
-        from_params(params, fields);
+    fn parse_block(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        if field == "count" {
+            return true;
+        }
+        let (index, field) = match split_index(field) {
+            Some(v) => v,
+            None => return false,
+        };
+        let block = nth_mut(&mut self.blocks, index);
+        let slot = match field {
+            "name" => {
+                block.name = to_string(value);
+                return true;
+            }
+            "path" => {
+                block.path = to_string(value);
+                return true;
+            }
+            "backingIndex" => {
+                block.backing_index = to_u32(value);
+                return true;
+            }
+            "rd.reqs" => &mut block.rd_reqs,
+            "rd.bytes" => &mut block.rd_bytes,
+            "rd.times" => &mut block.rd_times,
+            "wr.reqs" => &mut block.wr_reqs,
+            "wr.bytes" => &mut block.wr_bytes,
+            "wr.times" => &mut block.wr_times,
+            "fl.reqs" => &mut block.fl_reqs,
+            "fl.times" => &mut block.fl_times,
+            "errors" => &mut block.errors,
+            "allocation" => &mut block.allocation,
+            "capacity" => &mut block.capacity,
+            "physical" => &mut block.physical,
+            "threshold" => &mut block.threshold,
+            _ => return false,
+        };
+        *slot = value.as_u64();
+        true
+    }
+
+    fn parse_perf(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        let perf = self.perf.get_or_insert_with(Default::default);
+        let slot = match field {
+            "cmt" => &mut perf.cmt,
+            "mbmt" => &mut perf.mbmt,
+            "mbml" => &mut perf.mbml,
+            "cache_misses" => &mut perf.cache_misses,
+            "cache_references" => &mut perf.cache_references,
+            "instructions" => &mut perf.instructions,
+            "cpu_cycles" => &mut perf.cpu_cycles,
+            "branch_instructions" => &mut perf.branch_instructions,
+            "branch_misses" => &mut perf.branch_misses,
+            "bus_cycles" => &mut perf.bus_cycles,
+            "stalled_cycles_frontend" => &mut perf.stalled_cycles_frontend,
+            "stalled_cycles_backend" => &mut perf.stalled_cycles_backend,
+            "ref_cpu_cycles" => &mut perf.ref_cpu_cycles,
+            "cpu_clock" => &mut perf.cpu_clock,
+            "task_clock" => &mut perf.task_clock,
+            "page_faults" => &mut perf.page_faults,
+            "context_switches" => &mut perf.context_switches,
+            "cpu_migrations" => &mut perf.cpu_migrations,
+            "page_faults_min" => &mut perf.page_faults_min,
+            "page_faults_maj" => &mut perf.page_faults_maj,
+            "alignment_faults" => &mut perf.alignment_faults,
+            "emulation_faults" => &mut perf.emulation_faults,
+            _ => return false,
+        };
+        *slot = value.as_u64();
+        true
+    }
 
-        stats
+    fn parse_iothread(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        if field == "count" {
+            return true;
+        }
+        let (id, field) = match split_index(field) {
+            Some((id, field)) => (id as u32, field),
+            None => return false,
+        };
+        // IOThreads are keyed by their identifier, not by position.
+        let pos = match self.iothreads.iter().position(|t| t.id == id) {
+            Some(pos) => pos,
+            None => {
+                self.iothreads.push(DomainStatsIOThread {
+                    id,
+                    ..Default::default()
+                });
+                self.iothreads.len() - 1
+            }
+        };
+        let iothread = &mut self.iothreads[pos];
+        let slot = match field {
+            "poll-max-ns" => &mut iothread.poll_max_ns,
+            "poll-grow" => &mut iothread.poll_grow,
+            "poll-shrink" => &mut iothread.poll_shrink,
+            _ => return false,
+        };
+        *slot = value.as_u64();
+        true
+    }
+
+    fn parse_memory_bandwidth(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        if field == "count" {
+            return true;
+        }
+        let (index, field) = match split_index(field) {
+            Some(v) => v,
+            None => return false,
+        };
+        let monitor = nth_mut(&mut self.memory_bandwidth, index);
+        match field {
+            "name" => monitor.name = to_string(value),
+            "vcpus" => monitor.vcpus = to_string(value),
+            "node.count" => {}
+            _ => {
+                let (index, field) = match field.strip_prefix("node.").and_then(split_index) {
+                    Some(v) => v,
+                    None => return false,
+                };
+                let node = nth_mut(&mut monitor.nodes, index);
+                match field {
+                    "id" => node.id = to_u32(value),
+                    "bytes.local" => node.bytes_local = value.as_u64(),
+                    "bytes.total" => node.bytes_total = value.as_u64(),
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+
+    fn parse_dirty_rate(&mut self, field: &str, value: &TypedParamValue) -> bool {
+        let dirty_rate = self.dirty_rate.get_or_insert_with(Default::default);
+        match field {
+            "calc_status" => dirty_rate.calc_status = to_i32(value),
+            "calc_start_time" => dirty_rate.calc_start_time = value.as_i64(),
+            "calc_period" => dirty_rate.calc_period = to_i32(value),
+            "megabytes_per_second" => dirty_rate.megabytes_per_second = value.as_i64(),
+            "calc_mode" => dirty_rate.calc_mode = to_string(value),
+            _ => {
+                let vcpu = field
+                    .strip_prefix("vcpu.")
+                    .and_then(split_index)
+                    .filter(|(_, field)| *field == "megabytes_per_second");
+                match vcpu {
+                    Some((index, _)) => *nth_mut(&mut dirty_rate.vcpus, index) = value.as_i64(),
+                    None => return false,
+                }
+            }
+        }
+        true
     }
 }
 
-/// Structure representing the CFS scheduler cpu bandwidth parameters
-/// see <https://www.kernel.org/doc/html/latest/scheduler/sched-bwc.html>
+/// User logged in the guest.
 #[derive(Clone, Debug, Default)]
-pub struct SchedBandwidth {
-    pub period: Option<u64>,
-    pub quota: Option<i64>,
+pub struct GuestUser {
+    pub name: Option<String>,
+    /// Domain of the user, on Windows guests.
+    pub domain: Option<String>,
+    /// Login time, in milliseconds since the epoch.
+    pub login_time: Option<u64>,
 }
 
+/// Operating system of the guest.
 #[derive(Clone, Debug, Default)]
-pub struct SchedulerInfo {
-    pub scheduler_type: String,
-    // cpu shares for the domain.
-    pub cpu_shares: Option<u64>,
-    // Bandwidth allocated for the vcpu threads.
-    pub vcpu_bw: SchedBandwidth,
-    // Bandwidth allocated for the emulator threads.
-    pub emulator_bw: SchedBandwidth,
-    // Bandwidth allocated for the Domain.
-    pub global_bw: SchedBandwidth,
-    // Bandwidth allocated for the io threads..
-    pub iothread_bw: SchedBandwidth,
-    // Credit scheduler relative weight
-    pub weight: Option<u32>,
-    // Credit scheduler cap
-    pub cap: Option<u32>,
-    // Allocation scheduler reservation
-    pub reservation: Option<i64>,
+pub struct GuestOSInfo {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub pretty_name: Option<String>,
+    pub version: Option<String>,
+    pub version_id: Option<String>,
+    pub kernel_release: Option<String>,
+    pub kernel_version: Option<String>,
+    pub machine: Option<String>,
+    pub variant: Option<String>,
+    pub variant_id: Option<String>,
+}
+
+/// Timezone of the guest.
+#[derive(Clone, Debug, Default)]
+pub struct GuestTimezone {
+    pub name: Option<String>,
+    /// Offset to UTC, in seconds.
+    pub offset: Option<i32>,
+}
+
+/// Disk backing a guest filesystem.
+#[derive(Clone, Debug, Default)]
+pub struct GuestFilesystemDisk {
+    /// Alias of the disk in the domain XML.
+    pub alias: Option<String>,
+    pub serial: Option<String>,
+    /// Device node of the disk in the guest.
+    pub device: Option<String>,
+}
+
+/// Filesystem mounted in the guest.
+#[derive(Clone, Debug, Default)]
+pub struct GuestFilesystem {
+    pub mountpoint: Option<String>,
+    /// Name of the device in the guest, e.g. `sda1`.
+    pub name: Option<String>,
+    pub fstype: Option<String>,
+    pub total_bytes: Option<u64>,
+    pub used_bytes: Option<u64>,
+    pub disks: Vec<GuestFilesystemDisk>,
+}
+
+/// Disk as seen by the guest.
+#[derive(Clone, Debug, Default)]
+pub struct GuestDisk {
+    /// Device node of the disk in the guest.
+    pub name: Option<String>,
+    /// Whether the disk is a partition.
+    pub partition: Option<bool>,
+    /// Device nodes of the disks this one depends on, e.g. the disks
+    /// of a RAID array.
+    pub dependencies: Vec<String>,
+    pub serial: Option<String>,
+    /// Alias of the disk in the domain XML.
+    pub alias: Option<String>,
+    /// Optional alias assigned to the disk in the guest.
+    pub guest_alias: Option<String>,
+    /// Bus of the disk as seen by the guest.
+    pub guest_bus: Option<String>,
+}
+
+/// Address of a guest network interface.
+#[derive(Clone, Debug, Default)]
+pub struct GuestInterfaceAddress {
+    /// Either `"ipv4"` or `"ipv6"`.
+    pub r#type: Option<String>,
+    pub addr: Option<String>,
+    pub prefix: Option<u32>,
+}
+
+/// Network interface of the guest.
+#[derive(Clone, Debug, Default)]
+pub struct GuestInterface {
+    pub name: Option<String>,
+    pub hwaddr: Option<String>,
+    pub addrs: Vec<GuestInterfaceAddress>,
+}
+
+/// Load averages of the guest.
+#[derive(Clone, Debug, Default)]
+pub struct GuestLoad {
+    pub load_1m: Option<f64>,
+    pub load_5m: Option<f64>,
+    pub load_15m: Option<f64>,
+}
+
+/// Information reported by the guest agent, see
+/// [`Domain::guest_info`].
+///
+/// Only the groups that were requested and are supported by the
+/// agent are filled.
+#[derive(Clone, Debug, Default)]
+pub struct GuestInfo {
+    pub users: Vec<GuestUser>,
+    pub os: Option<GuestOSInfo>,
+    pub timezone: Option<GuestTimezone>,
+    pub hostname: Option<String>,
+    pub filesystems: Vec<GuestFilesystem>,
+    pub disks: Vec<GuestDisk>,
+    pub interfaces: Vec<GuestInterface>,
+    pub load: Option<GuestLoad>,
+}
+
+impl GuestInfo {
+    /// Builds the information from the flat `fs.0.name` style
+    /// parameters returned by libvirt. Unknown parameters are
+    /// ignored.
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> GuestInfo {
+        let mut ret = GuestInfo::default();
+        for (key, value) in from_params_generic(&vec) {
+            match key.split_once('.') {
+                Some(("user", field)) => ret.parse_user(field, &value),
+                Some(("os", field)) => ret.parse_os(field, &value),
+                Some(("timezone", field)) => ret.parse_timezone(field, &value),
+                Some(("fs", field)) => ret.parse_fs(field, &value),
+                Some(("disk", field)) => ret.parse_disk(field, &value),
+                Some(("if", field)) => ret.parse_if(field, &value),
+                Some(("load", field)) => ret.parse_load(field, &value),
+                None if key == "hostname" => ret.hostname = to_string(&value),
+                _ => {}
+            }
+        }
+        ret
+    }
+
+    fn parse_user(&mut self, field: &str, value: &TypedParamValue) {
+        if let Some((index, field)) = split_index(field) {
+            let user = nth_mut(&mut self.users, index);
+            match field {
+                "name" => user.name = to_string(value),
+                "domain" => user.domain = to_string(value),
+                "login-time" => user.login_time = value.as_u64(),
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_os(&mut self, field: &str, value: &TypedParamValue) {
+        let os = self.os.get_or_insert_with(Default::default);
+        let slot = match field {
+            "id" => &mut os.id,
+            "name" => &mut os.name,
+            "pretty-name" => &mut os.pretty_name,
+            "version" => &mut os.version,
+            "version-id" => &mut os.version_id,
+            "kernel-release" => &mut os.kernel_release,
+            "kernel-version" => &mut os.kernel_version,
+            "machine" => &mut os.machine,
+            "variant" => &mut os.variant,
+            "variant-id" => &mut os.variant_id,
+            _ => return,
+        };
+        *slot = to_string(value);
+    }
+
+    fn parse_timezone(&mut self, field: &str, value: &TypedParamValue) {
+        let timezone = self.timezone.get_or_insert_with(Default::default);
+        match field {
+            "name" => timezone.name = to_string(value),
+            "offset" => timezone.offset = to_i32(value),
+            _ => {}
+        }
+    }
+
+    fn parse_fs(&mut self, field: &str, value: &TypedParamValue) {
+        if let Some((index, field)) = split_index(field) {
+            let fs = nth_mut(&mut self.filesystems, index);
+            match field {
+                "mountpoint" => fs.mountpoint = to_string(value),
+                "name" => fs.name = to_string(value),
+                "fstype" => fs.fstype = to_string(value),
+                "total-bytes" => fs.total_bytes = value.as_u64(),
+                "used-bytes" => fs.used_bytes = value.as_u64(),
+                _ => {
+                    if let Some((index, field)) = field.strip_prefix("disk.").and_then(split_index)
+                    {
+                        let disk = nth_mut(&mut fs.disks, index);
+                        match field {
+                            "alias" => disk.alias = to_string(value),
+                            "serial" => disk.serial = to_string(value),
+                            "device" => disk.device = to_string(value),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_disk(&mut self, field: &str, value: &TypedParamValue) {
+        if let Some((index, field)) = split_index(field) {
+            let disk = nth_mut(&mut self.disks, index);
+            match field {
+                "name" => disk.name = to_string(value),
+                "partition" => disk.partition = value.as_bool(),
+                "serial" => disk.serial = to_string(value),
+                "alias" => disk.alias = to_string(value),
+                "guest_alias" => disk.guest_alias = to_string(value),
+                "guest_bus" => disk.guest_bus = to_string(value),
+                _ => {
+                    let dependency = field
+                        .strip_prefix("dependency.")
+                        .and_then(split_index)
+                        .filter(|(_, field)| *field == "name");
+                    if let (Some((index, _)), Some(name)) = (dependency, value.as_str()) {
+                        *nth_mut(&mut disk.dependencies, index) = name.to_string();
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_if(&mut self, field: &str, value: &TypedParamValue) {
+        if let Some((index, field)) = split_index(field) {
+            let iface = nth_mut(&mut self.interfaces, index);
+            match field {
+                "name" => iface.name = to_string(value),
+                "hwaddr" => iface.hwaddr = to_string(value),
+                _ => {
+                    if let Some((index, field)) = field.strip_prefix("addr.").and_then(split_index)
+                    {
+                        let addr = nth_mut(&mut iface.addrs, index);
+                        match field {
+                            "type" => addr.r#type = to_string(value),
+                            "addr" => addr.addr = to_string(value),
+                            "prefix" => addr.prefix = to_u32(value),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_load(&mut self, field: &str, value: &TypedParamValue) {
+        let load = self.load.get_or_insert_with(Default::default);
+        match field {
+            "1m" => load.load_1m = value.as_f64(),
+            "5m" => load.load_5m = value.as_f64(),
+            "15m" => load.load_15m = value.as_f64(),
+            _ => {}
+        }
+    }
+}
+
+/// Filesystem mounted in the guest, see [`Domain::get_fs_info`].
+#[derive(Clone, Debug)]
+pub struct FSInfo {
+    /// Path of the mount point.
+    pub mountpoint: String,
+    /// Device name in the guest, e.g. `sda1`.
+    pub name: String,
+    /// Filesystem type.
+    pub fstype: String,
+    /// Aliases of the disks backing the filesystem, in the domain
+    /// XML.
+    pub dev_aliases: Vec<String>,
+}
+
+impl FSInfo {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(ptr: sys::virDomainFSInfoPtr) -> FSInfo {
+        let aliases = if (*ptr).devAlias.is_null() {
+            &[]
+        } else {
+            slice::from_raw_parts((*ptr).devAlias, (*ptr).ndevAlias)
+        };
+        FSInfo {
+            mountpoint: c_chars_to_string!((*ptr).mountpoint, nofree),
+            name: c_chars_to_string!((*ptr).name, nofree),
+            fstype: c_chars_to_string!((*ptr).fstype, nofree),
+            dev_aliases: aliases
+                .iter()
+                .map(|&alias| c_chars_to_string!(alias, nofree))
+                .collect(),
+        }
+    }
+}
+
+/// Guard returned by [`Domain::fs_freeze`], thawing the frozen
+/// filesystems when dropped.
+///
+/// All the guest filesystems are thawed, whichever were frozen: the
+/// QEMU guest agent can only thaw them all at once.
+///
+/// Errors while thawing on drop are ignored, use
+/// [`FSFreezeGuard::thaw`] to handle them.
+#[derive(Debug)]
+pub struct FSFreezeGuard {
+    domain: Domain,
+    frozen: u32,
+    thawed: bool,
+}
+
+impl FSFreezeGuard {
+    /// Returns the number of filesystems that were frozen.
+    pub fn frozen(&self) -> u32 {
+        self.frozen
+    }
+
+    /// Thaws the filesystems, returning the number of thawed
+    /// filesystems.
+    pub fn thaw(mut self) -> Result<u32, Error> {
+        self.thawed = true;
+        self.domain.fs_thaw(&[], 0)
+    }
+}
+
+impl Drop for FSFreezeGuard {
+    fn drop(&mut self) {
+        if !self.thawed {
+            let _ = self.domain.fs_thaw(&[], 0);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BlockInfo {
+    /// Logical size in bytes of the image (how much storage the guest
+    /// will see).
+    pub capacity: u64,
+    /// Host storage in bytes occupied by the image (such as highest
+    /// allocated extent if there are no holes, similar to 'du').
+    pub allocation: u64,
+    /// Host physical size in bytes of the image container (last
+    /// offset, similar to 'ls')
+    pub physical: u64,
+}
+
+impl BlockInfo {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(ptr: sys::virDomainBlockInfoPtr) -> BlockInfo {
+        BlockInfo {
+            capacity: (*ptr).capacity,
+            allocation: (*ptr).allocation,
+            physical: (*ptr).physical,
+        }
+    }
+}
+
+/// Progress of a block job, see [`Domain::block_job_info`].
+#[derive(Clone, Debug)]
+pub struct BlockJobInfo {
+    /// Type of the running job.
+    pub r#type: DomainBlockJobTypeEnum,
+    /// Bandwidth limit, in MiB/s or in bytes/s with
+    /// [`BlockJobInfoFlags::BANDWIDTH_BYTES`].
+    pub bandwidth: u64,
+    /// Progress of the job, as a value between 0 and `end`.
+    pub cur: u64,
+    /// Value of `cur` once the job is done. It may change while the
+    /// job is running.
+    pub end: u64,
+}
+
+impl BlockJobInfo {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(ptr: sys::virDomainBlockJobInfoPtr) -> BlockJobInfo {
+        BlockJobInfo {
+            r#type: DomainBlockJobTypeEnum::from_raw((*ptr).type_ as sys::virDomainBlockJobType),
+            bandwidth: c_ulong_to_u64((*ptr).bandwidth),
+            cur: (*ptr).cur,
+            end: (*ptr).end,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CpuStats {
+    /// cpu usage (sum of both vcpu and hypervisor usage) in nanoseconds
+    pub cpu_time: Option<u64>,
+    /// cpu time charged to user instructions in nanoseconds
+    pub user_time: Option<u64>,
+    /// cpu time charged to system instructions in nanoseconds
+    pub system_time: Option<u64>,
+    /// vcpu usage in nanoseconds (cpu_time excluding hypervisor time)
+    pub vcpu_time: Option<u64>,
+}
+
+macro_rules! cpu_stats_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(sys::VIR_DOMAIN_CPU_STATS_CPUTIME, UInt64, $var.cpu_time),
+            $dir!(sys::VIR_DOMAIN_CPU_STATS_USERTIME, UInt64, $var.user_time),
+            $dir!(
+                sys::VIR_DOMAIN_CPU_STATS_SYSTEMTIME,
+                UInt64,
+                $var.system_time
+            ),
+            $dir!(sys::VIR_DOMAIN_CPU_STATS_VCPUTIME, UInt64, $var.vcpu_time),
+        ]
+    };
+}
+
+impl CpuStats {
+    fn from_vec(vec: Vec<sys::virTypedParameter>) -> CpuStats {
+        let mut ret = CpuStats::default();
+        let fields = cpu_stats_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryParameters {
+    /// Represents the maximum memory the guest can use.
+    pub hard_limit: Option<u64>,
+    /// Represents the memory upper limit enforced during memory
+    /// contention.
+    pub soft_limit: Option<u64>,
+    /// Represents the minimum memory guaranteed to be reserved for
+    /// the guest.
+    pub min_guarantee: Option<u64>,
+    /// Represents the maximum swap plus memory the guest can use.
+    pub swap_hard_limit: Option<u64>,
+}
+
+macro_rules! memory_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(sys::VIR_DOMAIN_MEMORY_HARD_LIMIT, UInt64, $var.hard_limit),
+            $dir!(sys::VIR_DOMAIN_MEMORY_SOFT_LIMIT, UInt64, $var.soft_limit),
+            $dir!(
+                sys::VIR_DOMAIN_MEMORY_MIN_GUARANTEE,
+                UInt64,
+                $var.min_guarantee
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_MEMORY_SWAP_HARD_LIMIT,
+                UInt64,
+                $var.swap_hard_limit
+            ),
+        ]
+    };
+}
+
+impl MemoryParameters {
+    pub const VALUE_UNLIMITED: u64 = sys::VIR_DOMAIN_MEMORY_PARAM_UNLIMITED;
+
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> MemoryParameters {
+        let mut ret = MemoryParameters::default();
+        let fields = memory_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = memory_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BlockCopyParameters {
+    /// Maximum bandwidth of the copy, in bytes/s.
+    pub bandwidth: Option<u64>,
+    /// Granularity of the dirty bitmap, in bytes. Must be a power of
+    /// 2 between 512 and 64 MiB.
+    pub granularity: Option<u32>,
+    /// Maximum amount of in-flight data, in bytes.
+    pub buf_size: Option<u64>,
+}
+
+macro_rules! block_copy_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(sys::VIR_DOMAIN_BLOCK_COPY_BANDWIDTH, UInt64, $var.bandwidth),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_COPY_GRANULARITY,
+                UInt32,
+                $var.granularity
+            ),
+            $dir!(sys::VIR_DOMAIN_BLOCK_COPY_BUF_SIZE, UInt64, $var.buf_size),
+        ]
+    };
+}
+
+impl BlockCopyParameters {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> BlockCopyParameters {
+        let mut ret = BlockCopyParameters::default();
+        let fields = block_copy_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = block_copy_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BlockIoTuneParameters {
+    /// Limit of total bytes per second.
+    pub total_bytes_sec: Option<u64>,
+    /// Limit of read bytes per second.
+    pub read_bytes_sec: Option<u64>,
+    /// Limit of write bytes per second.
+    pub write_bytes_sec: Option<u64>,
+    /// Limit of total I/O operations per second.
+    pub total_iops_sec: Option<u64>,
+    /// Limit of read I/O operations per second.
+    pub read_iops_sec: Option<u64>,
+    /// Limit of write I/O operations per second.
+    pub write_iops_sec: Option<u64>,
+    /// Maximum total bytes per second allowed in a burst.
+    pub total_bytes_sec_max: Option<u64>,
+    /// Maximum read bytes per second allowed in a burst.
+    pub read_bytes_sec_max: Option<u64>,
+    /// Maximum write bytes per second allowed in a burst.
+    pub write_bytes_sec_max: Option<u64>,
+    /// Maximum total I/O operations per second allowed in a burst.
+    pub total_iops_sec_max: Option<u64>,
+    /// Maximum read I/O operations per second allowed in a burst.
+    pub read_iops_sec_max: Option<u64>,
+    /// Maximum write I/O operations per second allowed in a burst.
+    pub write_iops_sec_max: Option<u64>,
+    /// Duration in seconds of a burst of total bytes at the
+    /// `total_bytes_sec_max` rate.
+    pub total_bytes_sec_max_length: Option<u64>,
+    /// Duration in seconds of a burst of read bytes at the
+    /// `read_bytes_sec_max` rate.
+    pub read_bytes_sec_max_length: Option<u64>,
+    /// Duration in seconds of a burst of write bytes at the
+    /// `write_bytes_sec_max` rate.
+    pub write_bytes_sec_max_length: Option<u64>,
+    /// Duration in seconds of a burst of total I/O operations at the
+    /// `total_iops_sec_max` rate.
+    pub total_iops_sec_max_length: Option<u64>,
+    /// Duration in seconds of a burst of read I/O operations at the
+    /// `read_iops_sec_max` rate.
+    pub read_iops_sec_max_length: Option<u64>,
+    /// Duration in seconds of a burst of write I/O operations at the
+    /// `write_iops_sec_max` rate.
+    pub write_iops_sec_max_length: Option<u64>,
+    /// Size of an I/O operation, in bytes, accounted as several
+    /// operations by the IOPS limits when it is larger.
+    pub size_iops_sec: Option<u64>,
+    /// Name of the group sharing its limits with other disks.
+    pub group_name: Option<String>,
+}
+
+macro_rules! block_io_tune_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_TOTAL_BYTES_SEC,
+                UInt64,
+                $var.total_bytes_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_READ_BYTES_SEC,
+                UInt64,
+                $var.read_bytes_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_WRITE_BYTES_SEC,
+                UInt64,
+                $var.write_bytes_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_TOTAL_IOPS_SEC,
+                UInt64,
+                $var.total_iops_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_READ_IOPS_SEC,
+                UInt64,
+                $var.read_iops_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_WRITE_IOPS_SEC,
+                UInt64,
+                $var.write_iops_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_TOTAL_BYTES_SEC_MAX,
+                UInt64,
+                $var.total_bytes_sec_max
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_READ_BYTES_SEC_MAX,
+                UInt64,
+                $var.read_bytes_sec_max
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_WRITE_BYTES_SEC_MAX,
+                UInt64,
+                $var.write_bytes_sec_max
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_TOTAL_IOPS_SEC_MAX,
+                UInt64,
+                $var.total_iops_sec_max
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_READ_IOPS_SEC_MAX,
+                UInt64,
+                $var.read_iops_sec_max
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_WRITE_IOPS_SEC_MAX,
+                UInt64,
+                $var.write_iops_sec_max
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_TOTAL_BYTES_SEC_MAX_LENGTH,
+                UInt64,
+                $var.total_bytes_sec_max_length
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_READ_BYTES_SEC_MAX_LENGTH,
+                UInt64,
+                $var.read_bytes_sec_max_length
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_WRITE_BYTES_SEC_MAX_LENGTH,
+                UInt64,
+                $var.write_bytes_sec_max_length
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_TOTAL_IOPS_SEC_MAX_LENGTH,
+                UInt64,
+                $var.total_iops_sec_max_length
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_READ_IOPS_SEC_MAX_LENGTH,
+                UInt64,
+                $var.read_iops_sec_max_length
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_WRITE_IOPS_SEC_MAX_LENGTH,
+                UInt64,
+                $var.write_iops_sec_max_length
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_SIZE_IOPS_SEC,
+                UInt64,
+                $var.size_iops_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLOCK_IOTUNE_GROUP_NAME,
+                String,
+                $var.group_name
+            ),
+        ]
+    };
+}
+
+impl BlockIoTuneParameters {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> BlockIoTuneParameters {
+        let mut ret = BlockIoTuneParameters::default();
+        let fields = block_io_tune_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = block_io_tune_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BlkioParameters {
+    /// Weight of the domain, between 100 and 1000.
+    pub weight: Option<u32>,
+    /// Per device weights, as a list of `path,weight` pairs
+    /// separated by commas.
+    pub device_weight: Option<String>,
+    /// Per device limits of read I/O operations per second, as a
+    /// list of `path,limit` pairs separated by commas.
+    pub device_read_iops_sec: Option<String>,
+    /// Per device limits of write I/O operations per second, as a
+    /// list of `path,limit` pairs separated by commas.
+    pub device_write_iops_sec: Option<String>,
+    /// Per device limits of read bytes per second, as a list of
+    /// `path,limit` pairs separated by commas.
+    pub device_read_bytes_sec: Option<String>,
+    /// Per device limits of write bytes per second, as a list of
+    /// `path,limit` pairs separated by commas.
+    pub device_write_bytes_sec: Option<String>,
+}
+
+macro_rules! blkio_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(sys::VIR_DOMAIN_BLKIO_WEIGHT, UInt32, $var.weight),
+            $dir!(
+                sys::VIR_DOMAIN_BLKIO_DEVICE_WEIGHT,
+                String,
+                $var.device_weight
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLKIO_DEVICE_READ_IOPS,
+                String,
+                $var.device_read_iops_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLKIO_DEVICE_WRITE_IOPS,
+                String,
+                $var.device_write_iops_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLKIO_DEVICE_READ_BPS,
+                String,
+                $var.device_read_bytes_sec
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BLKIO_DEVICE_WRITE_BPS,
+                String,
+                $var.device_write_bytes_sec
+            ),
+        ]
+    };
+}
+
+impl BlkioParameters {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> BlkioParameters {
+        let mut ret = BlkioParameters::default();
+        let fields = blkio_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = blkio_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct InterfaceParameters {
+    /// Average inbound bandwidth, in KiB/s.
+    pub inbound_average: Option<u32>,
+    /// Peak inbound bandwidth, in KiB/s.
+    pub inbound_peak: Option<u32>,
+    /// Amount of inbound data that can be burst at peak speed, in
+    /// KiB.
+    pub inbound_burst: Option<u32>,
+    /// Minimal inbound bandwidth guaranteed, in KiB/s.
+    pub inbound_floor: Option<u32>,
+    /// Average outbound bandwidth, in KiB/s.
+    pub outbound_average: Option<u32>,
+    /// Peak outbound bandwidth, in KiB/s.
+    pub outbound_peak: Option<u32>,
+    /// Amount of outbound data that can be burst at peak speed, in
+    /// KiB.
+    pub outbound_burst: Option<u32>,
+}
+
+macro_rules! interface_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(
+                sys::VIR_DOMAIN_BANDWIDTH_IN_AVERAGE,
+                UInt32,
+                $var.inbound_average
+            ),
+            $dir!(sys::VIR_DOMAIN_BANDWIDTH_IN_PEAK, UInt32, $var.inbound_peak),
+            $dir!(
+                sys::VIR_DOMAIN_BANDWIDTH_IN_BURST,
+                UInt32,
+                $var.inbound_burst
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BANDWIDTH_IN_FLOOR,
+                UInt32,
+                $var.inbound_floor
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BANDWIDTH_OUT_AVERAGE,
+                UInt32,
+                $var.outbound_average
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BANDWIDTH_OUT_PEAK,
+                UInt32,
+                $var.outbound_peak
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_BANDWIDTH_OUT_BURST,
+                UInt32,
+                $var.outbound_burst
+            ),
+        ]
+    };
+}
+
+impl InterfaceParameters {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> InterfaceParameters {
+        let mut ret = InterfaceParameters::default();
+        let fields = interface_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = interface_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+/// Perf events enabled for a domain, whose counters are reported in
+/// [`DomainStatsPerf`].
+#[derive(Clone, Debug, Default)]
+pub struct PerfEvents {
+    pub cmt: Option<bool>,
+    pub mbmt: Option<bool>,
+    pub mbml: Option<bool>,
+    pub cache_misses: Option<bool>,
+    pub cache_references: Option<bool>,
+    pub instructions: Option<bool>,
+    pub cpu_cycles: Option<bool>,
+    pub branch_instructions: Option<bool>,
+    pub branch_misses: Option<bool>,
+    pub bus_cycles: Option<bool>,
+    pub stalled_cycles_frontend: Option<bool>,
+    pub stalled_cycles_backend: Option<bool>,
+    pub ref_cpu_cycles: Option<bool>,
+    pub cpu_clock: Option<bool>,
+    pub task_clock: Option<bool>,
+    pub page_faults: Option<bool>,
+    pub context_switches: Option<bool>,
+    pub cpu_migrations: Option<bool>,
+    pub page_faults_min: Option<bool>,
+    pub page_faults_maj: Option<bool>,
+    pub alignment_faults: Option<bool>,
+    pub emulation_faults: Option<bool>,
+}
+
+macro_rules! perf_events_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(sys::VIR_PERF_PARAM_CMT, Bool, $var.cmt),
+            $dir!(sys::VIR_PERF_PARAM_MBMT, Bool, $var.mbmt),
+            $dir!(sys::VIR_PERF_PARAM_MBML, Bool, $var.mbml),
+            $dir!(sys::VIR_PERF_PARAM_CACHE_MISSES, Bool, $var.cache_misses),
+            $dir!(
+                sys::VIR_PERF_PARAM_CACHE_REFERENCES,
+                Bool,
+                $var.cache_references
+            ),
+            $dir!(sys::VIR_PERF_PARAM_INSTRUCTIONS, Bool, $var.instructions),
+            $dir!(sys::VIR_PERF_PARAM_CPU_CYCLES, Bool, $var.cpu_cycles),
+            $dir!(
+                sys::VIR_PERF_PARAM_BRANCH_INSTRUCTIONS,
+                Bool,
+                $var.branch_instructions
+            ),
+            $dir!(sys::VIR_PERF_PARAM_BRANCH_MISSES, Bool, $var.branch_misses),
+            $dir!(sys::VIR_PERF_PARAM_BUS_CYCLES, Bool, $var.bus_cycles),
+            $dir!(
+                sys::VIR_PERF_PARAM_STALLED_CYCLES_FRONTEND,
+                Bool,
+                $var.stalled_cycles_frontend
+            ),
+            $dir!(
+                sys::VIR_PERF_PARAM_STALLED_CYCLES_BACKEND,
+                Bool,
+                $var.stalled_cycles_backend
+            ),
+            $dir!(
+                sys::VIR_PERF_PARAM_REF_CPU_CYCLES,
+                Bool,
+                $var.ref_cpu_cycles
+            ),
+            $dir!(sys::VIR_PERF_PARAM_CPU_CLOCK, Bool, $var.cpu_clock),
+            $dir!(sys::VIR_PERF_PARAM_TASK_CLOCK, Bool, $var.task_clock),
+            $dir!(sys::VIR_PERF_PARAM_PAGE_FAULTS, Bool, $var.page_faults),
+            $dir!(
+                sys::VIR_PERF_PARAM_CONTEXT_SWITCHES,
+                Bool,
+                $var.context_switches
+            ),
+            $dir!(
+                sys::VIR_PERF_PARAM_CPU_MIGRATIONS,
+                Bool,
+                $var.cpu_migrations
+            ),
+            $dir!(
+                sys::VIR_PERF_PARAM_PAGE_FAULTS_MIN,
+                Bool,
+                $var.page_faults_min
+            ),
+            $dir!(
+                sys::VIR_PERF_PARAM_PAGE_FAULTS_MAJ,
+                Bool,
+                $var.page_faults_maj
+            ),
+            $dir!(
+                sys::VIR_PERF_PARAM_ALIGNMENT_FAULTS,
+                Bool,
+                $var.alignment_faults
+            ),
+            $dir!(
+                sys::VIR_PERF_PARAM_EMULATION_FAULTS,
+                Bool,
+                $var.emulation_faults
+            ),
+        ]
+    };
+}
+
+impl PerfEvents {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> PerfEvents {
+        let mut ret = PerfEvents::default();
+        let fields = perf_events_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = perf_events_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct IOThreadParameters {
+    /// Maximum polling time in nanoseconds, 0 disables polling.
+    pub poll_max_ns: Option<u64>,
+    /// Factor by which the polling time grows, 0 lets the
+    /// hypervisor choose.
+    pub poll_grow: Option<u32>,
+    /// Divisor by which the polling time shrinks, 0 lets the
+    /// hypervisor choose.
+    pub poll_shrink: Option<u32>,
+    /// Minimum number of worker threads of the IOThread pool.
+    pub thread_pool_min: Option<i32>,
+    /// Maximum number of worker threads of the IOThread pool.
+    pub thread_pool_max: Option<i32>,
+}
+
+macro_rules! iothread_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(
+                sys::VIR_DOMAIN_IOTHREAD_POLL_MAX_NS,
+                UInt64,
+                $var.poll_max_ns
+            ),
+            $dir!(sys::VIR_DOMAIN_IOTHREAD_POLL_GROW, UInt32, $var.poll_grow),
+            $dir!(
+                sys::VIR_DOMAIN_IOTHREAD_POLL_SHRINK,
+                UInt32,
+                $var.poll_shrink
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_IOTHREAD_THREAD_POOL_MIN,
+                Int32,
+                $var.thread_pool_min
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_IOTHREAD_THREAD_POOL_MAX,
+                Int32,
+                $var.thread_pool_max
+            ),
+        ]
+    };
+}
+
+impl IOThreadParameters {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> IOThreadParameters {
+        let mut ret = IOThreadParameters::default();
+        let fields = iothread_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = iothread_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+macro_rules! numa_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(sys::VIR_DOMAIN_NUMA_NODESET, String, $var.node_set),
+            $dir!(sys::VIR_DOMAIN_NUMA_MODE, Int32, $var.mode),
+        ]
+    };
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NUMAParameters {
+    /// Lists the numa nodeset of a domain.
+    pub node_set: Option<String>,
+    /// Numa mode of a domain, as an int containing a
+    /// DomainNumatuneMemMode value.
+    pub mode: Option<i32>,
+}
+
+impl NUMAParameters {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> NUMAParameters {
+        let mut ret = NUMAParameters::default();
+        let fields = numa_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = numa_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+macro_rules! migrate_parameters_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_AUTO_CONVERGE_INCREMENT,
+                Int32,
+                $var.auto_converge_increment
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_AUTO_CONVERGE_INITIAL,
+                Int32,
+                $var.auto_converge_initial
+            ),
+            $dir!(sys::VIR_MIGRATE_PARAM_BANDWIDTH, UInt64, $var.bandwidth),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_BANDWIDTH_POSTCOPY,
+                UInt64,
+                $var.bandwidth_postcopy
+            ),
+            $dir!(sys::VIR_MIGRATE_PARAM_COMPRESSION, String, $var.compression),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_COMPRESSION_MT_DTHREADS,
+                Int32,
+                $var.compression_mt_dthreads
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_COMPRESSION_MT_LEVEL,
+                Int32,
+                $var.compression_mt_level
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_COMPRESSION_MT_THREADS,
+                Int32,
+                $var.compression_mt_threads
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_COMPRESSION_XBZRLE_CACHE,
+                UInt64,
+                $var.compression_xbzrle_cache
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_COMPRESSION_ZLIB_LEVEL,
+                Int32,
+                $var.compression_zlib_level
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_COMPRESSION_ZSTD_LEVEL,
+                Int32,
+                $var.compression_zstd_level
+            ),
+            $dir!(sys::VIR_MIGRATE_PARAM_DEST_NAME, String, $var.dest_name),
+            $dir!(sys::VIR_MIGRATE_PARAM_DEST_XML, String, $var.dest_xml),
+            $dir!(sys::VIR_MIGRATE_PARAM_DISKS_PORT, Int32, $var.disks_port),
+            $dir!(sys::VIR_MIGRATE_PARAM_DISKS_URI, String, $var.disks_uri),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_GRAPHICS_URI,
+                String,
+                $var.graphics_uri
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_LISTEN_ADDRESS,
+                String,
+                $var.listen_address
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_MIGRATE_DISKS,
+                VecString,
+                $var.migrate_disks
+            ),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_PARALLEL_CONNECTIONS,
+                Int32,
+                $var.parallel_connections
+            ),
+            $dir!(sys::VIR_MIGRATE_PARAM_PERSIST_XML, String, $var.persist_xml),
+            $dir!(
+                sys::VIR_MIGRATE_PARAM_TLS_DESTINATION,
+                String,
+                $var.tls_destination
+            ),
+            $dir!(sys::VIR_MIGRATE_PARAM_URI, String, $var.uri),
+        ]
+    };
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MigrateParameters {
+    pub auto_converge_increment: Option<i32>,
+    pub auto_converge_initial: Option<i32>,
+    pub bandwidth: Option<u64>,
+    pub bandwidth_postcopy: Option<u64>,
+    pub compression: Option<String>,
+    pub compression_mt_dthreads: Option<i32>,
+    pub compression_mt_level: Option<i32>,
+    pub compression_mt_threads: Option<i32>,
+    pub compression_xbzrle_cache: Option<u64>,
+    pub compression_zlib_level: Option<i32>,
+    pub compression_zstd_level: Option<i32>,
+    pub dest_name: Option<String>,
+    pub dest_xml: Option<String>,
+    pub disks_port: Option<i32>,
+    pub disks_uri: Option<String>,
+    pub graphics_uri: Option<String>,
+    pub listen_address: Option<String>,
+    pub migrate_disks: Vec<String>,
+    pub parallel_connections: Option<i32>,
+    pub persist_xml: Option<String>,
+    pub tls_destination: Option<String>,
+    pub uri: Option<String>,
+}
+
+impl MigrateParameters {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>) -> MigrateParameters {
+        let mut ret = MigrateParameters::default();
+        let fields = migrate_parameters_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = migrate_parameters_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IPAddress {
+    pub typed: i64,
+    pub addr: String,
+    pub prefix: u64,
+}
+
+impl IPAddress {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(ptr: sys::virDomainIPAddressPtr) -> IPAddress {
+        IPAddress {
+            typed: (*ptr).type_ as i64,
+            addr: c_chars_to_string!((*ptr).addr),
+            prefix: (*ptr).prefix as u64,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Interface {
+    pub name: String,
+    pub hwaddr: String,
+    pub naddrs: u64,
+    pub addrs: Vec<IPAddress>,
+}
+
+impl Interface {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(ptr: sys::virDomainInterfacePtr) -> Interface {
+        let naddrs = (*ptr).naddrs;
+        let mut addrs = vec![];
+        for x in 0..naddrs as isize {
+            addrs.push(IPAddress::from_ptr((*ptr).addrs.offset(x)));
+        }
+        Interface {
+            name: c_chars_to_string!((*ptr).name),
+            hwaddr: c_chars_to_string!((*ptr).hwaddr),
+            naddrs: naddrs as u64,
+            addrs,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct InterfaceStats {
+    pub rx_bytes: i64,
+    pub rx_packets: i64,
+    pub rx_errs: i64,
+    pub rx_drop: i64,
+    pub tx_bytes: i64,
+    pub tx_packets: i64,
+    pub tx_errs: i64,
+    pub tx_drop: i64,
+}
+
+impl InterfaceStats {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(ptr: sys::virDomainInterfaceStatsPtr) -> InterfaceStats {
+        InterfaceStats {
+            rx_bytes: (*ptr).rx_bytes,
+            rx_packets: (*ptr).rx_packets,
+            rx_errs: (*ptr).rx_errs,
+            rx_drop: (*ptr).rx_drop,
+            tx_bytes: (*ptr).tx_bytes,
+            tx_packets: (*ptr).tx_packets,
+            tx_errs: (*ptr).tx_errs,
+            tx_drop: (*ptr).tx_drop,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MemoryStat {
+    pub tag: u32,
+    pub val: u64,
+}
+
+impl MemoryStat {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(ptr: *const sys::virDomainMemoryStatStruct) -> MemoryStat {
+        MemoryStat {
+            tag: (*ptr).tag as u32,
+            val: (*ptr).val,
+        }
+    }
+}
+
+/// Information about the progress of a background job that is
+/// affecting a domain.
+#[derive(Clone, Debug, Default)]
+pub struct JobStats {
+    pub r#type: i32,
+
+    pub auto_converge_throttle: Option<i32>,
+
+    pub compression_bytes: Option<u64>,
+    pub compression_cache: Option<u64>,
+    pub compression_cache_misses: Option<u64>,
+    pub compression_overflow: Option<u64>,
+    pub compression_pages: Option<u64>,
+
+    pub data_processed: Option<u64>,
+    pub data_remaining: Option<u64>,
+    pub data_total: Option<u64>,
+
+    pub disk_bps: Option<u64>,
+    pub disk_processed: Option<u64>,
+    pub disk_remaining: Option<u64>,
+    pub disk_temp_total: Option<u64>,
+    pub disk_temp_used: Option<u64>,
+    pub disk_total: Option<u64>,
+
+    pub downtime: Option<u64>,
+    pub downtime_net: Option<u64>,
+
+    pub error_message: Option<String>,
+
+    pub mem_bps: Option<u64>,
+    pub mem_constant: Option<u64>,
+    pub mem_dirty_rate: Option<u64>,
+    pub mem_iteration: Option<u64>,
+    pub mem_normal: Option<u64>,
+    pub mem_normal_bytes: Option<u64>,
+    pub mem_page_size: Option<u64>,
+    pub mem_postcopy_reqs: Option<u64>,
+    pub mem_processed: Option<u64>,
+    pub mem_remaining: Option<u64>,
+    pub mem_total: Option<u64>,
+
+    pub operation: Option<i32>,
+
+    pub setup_time: Option<u64>,
+
+    pub success: Option<bool>,
+
+    pub time_elapsed: Option<u64>,
+    pub time_elapsed_net: Option<u64>,
+    pub time_remaining: Option<u64>,
+}
+
+macro_rules! job_stats_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(
+                sys::VIR_DOMAIN_JOB_AUTO_CONVERGE_THROTTLE,
+                Int32,
+                $var.auto_converge_throttle
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_COMPRESSION_BYTES,
+                UInt64,
+                $var.compression_bytes
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_COMPRESSION_CACHE,
+                UInt64,
+                $var.compression_cache
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_COMPRESSION_CACHE_MISSES,
+                UInt64,
+                $var.compression_cache_misses
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_COMPRESSION_OVERFLOW,
+                UInt64,
+                $var.compression_overflow
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_COMPRESSION_PAGES,
+                UInt64,
+                $var.compression_pages
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_DATA_PROCESSED,
+                UInt64,
+                $var.data_processed
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_DATA_REMAINING,
+                UInt64,
+                $var.data_remaining
+            ),
+            $dir!(sys::VIR_DOMAIN_JOB_DATA_TOTAL, UInt64, $var.data_total),
+            $dir!(sys::VIR_DOMAIN_JOB_DISK_BPS, UInt64, $var.disk_bps),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_DISK_PROCESSED,
+                UInt64,
+                $var.disk_processed
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_DISK_REMAINING,
+                UInt64,
+                $var.disk_remaining
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_DISK_TEMP_TOTAL,
+                UInt64,
+                $var.disk_temp_total
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_DISK_TEMP_USED,
+                UInt64,
+                $var.disk_temp_used
+            ),
+            $dir!(sys::VIR_DOMAIN_JOB_DISK_TOTAL, UInt64, $var.disk_total),
+            $dir!(sys::VIR_DOMAIN_JOB_DOWNTIME, UInt64, $var.downtime),
+            $dir!(sys::VIR_DOMAIN_JOB_DOWNTIME_NET, UInt64, $var.downtime_net),
+            $dir!(sys::VIR_DOMAIN_JOB_ERRMSG, String, $var.error_message),
+            $dir!(sys::VIR_DOMAIN_JOB_MEMORY_BPS, UInt64, $var.mem_bps),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_CONSTANT,
+                UInt64,
+                $var.mem_constant
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_DIRTY_RATE,
+                UInt64,
+                $var.mem_dirty_rate
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_ITERATION,
+                UInt64,
+                $var.mem_iteration
+            ),
+            $dir!(sys::VIR_DOMAIN_JOB_MEMORY_NORMAL, UInt64, $var.mem_normal),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_NORMAL_BYTES,
+                UInt64,
+                $var.mem_normal_bytes
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_PAGE_SIZE,
+                UInt64,
+                $var.mem_page_size
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_POSTCOPY_REQS,
+                UInt64,
+                $var.mem_postcopy_reqs
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_PROCESSED,
+                UInt64,
+                $var.mem_processed
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_MEMORY_REMAINING,
+                UInt64,
+                $var.mem_remaining
+            ),
+            $dir!(sys::VIR_DOMAIN_JOB_MEMORY_TOTAL, UInt64, $var.mem_total),
+            $dir!(sys::VIR_DOMAIN_JOB_OPERATION, Int32, $var.operation),
+            $dir!(sys::VIR_DOMAIN_JOB_SETUP_TIME, UInt64, $var.setup_time),
+            $dir!(sys::VIR_DOMAIN_JOB_SUCCESS, Bool, $var.success),
+            $dir!(sys::VIR_DOMAIN_JOB_TIME_ELAPSED, UInt64, $var.time_elapsed),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_TIME_ELAPSED_NET,
+                UInt64,
+                $var.time_elapsed_net
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_JOB_TIME_REMAINING,
+                UInt64,
+                $var.time_remaining
+            ),
+        ]
+    };
+}
+
+impl From<(i32, Vec<sys::virTypedParameter>)> for JobStats {
+    fn from((r#type, params): (i32, Vec<sys::virTypedParameter>)) -> Self {
+        let mut stats = Self {
+            r#type,
+            ..Default::default()
+        };
+
+        let fields = job_stats_fields!(param_field_in, stats);
+
+        from_params(params, fields);
+
+        stats
+    }
+}
+
+/// Structure representing the CFS scheduler cpu bandwidth parameters
+/// see <https://www.kernel.org/doc/html/latest/scheduler/sched-bwc.html>
+#[derive(Clone, Debug, Default)]
+pub struct SchedBandwidth {
+    pub period: Option<u64>,
+    pub quota: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SchedulerInfo {
+    pub scheduler_type: String,
+    // cpu shares for the domain.
+    pub cpu_shares: Option<u64>,
+    // Bandwidth allocated for the vcpu threads.
+    pub vcpu_bw: SchedBandwidth,
+    // Bandwidth allocated for the emulator threads.
+    pub emulator_bw: SchedBandwidth,
+    // Bandwidth allocated for the Domain.
+    pub global_bw: SchedBandwidth,
+    // Bandwidth allocated for the io threads..
+    pub iothread_bw: SchedBandwidth,
+    // Credit scheduler relative weight
+    pub weight: Option<u32>,
+    // Credit scheduler cap
+    pub cap: Option<u32>,
+    // Allocation scheduler reservation
+    pub reservation: Option<i64>,
     // Allocation scheduler limit
     pub limit: Option<i64>,
     // Allocation scheduler shares
     pub shares: Option<i32>,
 }
 
-macro_rules! scheduler_info_fields {
-    ($dir:ident, $var:ident) => {
-        vec![
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_CPU_SHARES,
-                UInt64,
-                $var.cpu_shares
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_VCPU_PERIOD,
-                UInt64,
-                $var.vcpu_bw.period
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_VCPU_QUOTA,
-                Int64,
-                $var.vcpu_bw.quota
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_EMULATOR_PERIOD,
-                UInt64,
-                $var.emulator_bw.period
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_EMULATOR_QUOTA,
-                Int64,
-                $var.emulator_bw.quota
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_GLOBAL_PERIOD,
-                UInt64,
-                $var.global_bw.period
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_GLOBAL_QUOTA,
-                Int64,
-                $var.global_bw.quota
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_IOTHREAD_PERIOD,
-                UInt64,
-                $var.iothread_bw.period
-            ),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_IOTHREAD_QUOTA,
-                Int64,
-                $var.iothread_bw.quota
-            ),
-            $dir!(sys::VIR_DOMAIN_SCHEDULER_WEIGHT, UInt32, $var.weight),
-            $dir!(sys::VIR_DOMAIN_SCHEDULER_CAP, UInt32, $var.cap),
-            $dir!(
-                sys::VIR_DOMAIN_SCHEDULER_RESERVATION,
-                Int64,
-                $var.reservation
-            ),
-            $dir!(sys::VIR_DOMAIN_SCHEDULER_LIMIT, Int64, $var.limit),
-            $dir!(sys::VIR_DOMAIN_SCHEDULER_SHARES, Int32, $var.shares),
-        ]
+macro_rules! scheduler_info_fields {
+    ($dir:ident, $var:ident) => {
+        vec![
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_CPU_SHARES,
+                UInt64,
+                $var.cpu_shares
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_VCPU_PERIOD,
+                UInt64,
+                $var.vcpu_bw.period
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_VCPU_QUOTA,
+                Int64,
+                $var.vcpu_bw.quota
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_EMULATOR_PERIOD,
+                UInt64,
+                $var.emulator_bw.period
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_EMULATOR_QUOTA,
+                Int64,
+                $var.emulator_bw.quota
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_GLOBAL_PERIOD,
+                UInt64,
+                $var.global_bw.period
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_GLOBAL_QUOTA,
+                Int64,
+                $var.global_bw.quota
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_IOTHREAD_PERIOD,
+                UInt64,
+                $var.iothread_bw.period
+            ),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_IOTHREAD_QUOTA,
+                Int64,
+                $var.iothread_bw.quota
+            ),
+            $dir!(sys::VIR_DOMAIN_SCHEDULER_WEIGHT, UInt32, $var.weight),
+            $dir!(sys::VIR_DOMAIN_SCHEDULER_CAP, UInt32, $var.cap),
+            $dir!(
+                sys::VIR_DOMAIN_SCHEDULER_RESERVATION,
+                Int64,
+                $var.reservation
+            ),
+            $dir!(sys::VIR_DOMAIN_SCHEDULER_LIMIT, Int64, $var.limit),
+            $dir!(sys::VIR_DOMAIN_SCHEDULER_SHARES, Int32, $var.shares),
+        ]
+    };
+}
+
+impl SchedulerInfo {
+    pub fn from_vec(vec: Vec<sys::virTypedParameter>, scheduler_type: String) -> SchedulerInfo {
+        let mut ret = SchedulerInfo {
+            scheduler_type,
+            ..Default::default()
+        };
+        let fields = scheduler_info_fields!(param_field_in, ret);
+        from_params(vec, fields);
+        ret
+    }
+
+    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
+        let fields = scheduler_info_fields!(param_field_out, self);
+        to_params(fields)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventWatchdogAction {
+    None,
+    Pause,
+    Reset,
+    PowerOff,
+    Shutdown,
+    Debug,
+    InjectNMI,
+}
+
+pub type DomainEventWatchdogActionEnum =
+    Enum<DomainEventWatchdogAction, sys::virDomainEventWatchdogAction>;
+
+impl_enum! {
+    enum: DomainEventWatchdogAction,
+    raw: sys::virDomainEventWatchdogAction,
+    match: {
+    sys::VIR_DOMAIN_EVENT_WATCHDOG_NONE => None,
+    sys::VIR_DOMAIN_EVENT_WATCHDOG_PAUSE => Pause,
+    sys::VIR_DOMAIN_EVENT_WATCHDOG_RESET => Reset,
+    sys::VIR_DOMAIN_EVENT_WATCHDOG_POWEROFF => PowerOff,
+    sys::VIR_DOMAIN_EVENT_WATCHDOG_SHUTDOWN => Shutdown,
+    sys::VIR_DOMAIN_EVENT_WATCHDOG_DEBUG => Debug,
+    sys::VIR_DOMAIN_EVENT_WATCHDOG_INJECTNMI => InjectNMI,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventIOErrorAction {
+    None,
+    Pause,
+    Report,
+}
+
+pub type DomainEventIOErrorActionEnum =
+    Enum<DomainEventIOErrorAction, sys::virDomainEventIOErrorAction>;
+
+impl_enum! {
+    enum: DomainEventIOErrorAction,
+    raw: sys::virDomainEventIOErrorAction,
+    match: {
+    sys::VIR_DOMAIN_EVENT_IO_ERROR_NONE => None,
+    sys::VIR_DOMAIN_EVENT_IO_ERROR_PAUSE => Pause,
+    sys::VIR_DOMAIN_EVENT_IO_ERROR_REPORT => Report,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventGraphicsPhase {
+    Connect,
+    Initialize,
+    Disconnect,
+}
+
+pub type DomainEventGraphicsPhaseEnum =
+    Enum<DomainEventGraphicsPhase, sys::virDomainEventGraphicsPhase>;
+
+impl_enum! {
+    enum: DomainEventGraphicsPhase,
+    raw: sys::virDomainEventGraphicsPhase,
+    match: {
+    sys::VIR_DOMAIN_EVENT_GRAPHICS_CONNECT => Connect,
+    sys::VIR_DOMAIN_EVENT_GRAPHICS_INITIALIZE => Initialize,
+    sys::VIR_DOMAIN_EVENT_GRAPHICS_DISCONNECT => Disconnect,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventGraphicsAddressType {
+    IPv4,
+    IPv6,
+    Unix,
+}
+
+pub type DomainEventGraphicsAddressTypeEnum =
+    Enum<DomainEventGraphicsAddressType, sys::virDomainEventGraphicsAddressType>;
+
+impl_enum! {
+    enum: DomainEventGraphicsAddressType,
+    raw: sys::virDomainEventGraphicsAddressType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_GRAPHICS_ADDRESS_IPV4 => IPv4,
+    sys::VIR_DOMAIN_EVENT_GRAPHICS_ADDRESS_IPV6 => IPv6,
+    sys::VIR_DOMAIN_EVENT_GRAPHICS_ADDRESS_UNIX => Unix,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DomainEventGraphicsAddress {
+    /// Address family.
+    pub family: DomainEventGraphicsAddressTypeEnum,
+    /// Address of node (eg IP address, or UNIX path).
+    pub node: String,
+    /// Service name/number (eg TCP port, or NULL).
+    pub service: Option<String>,
+}
+
+impl DomainEventGraphicsAddress {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(
+        ptr: *const sys::virDomainEventGraphicsAddress,
+    ) -> DomainEventGraphicsAddress {
+        DomainEventGraphicsAddress {
+            family: DomainEventGraphicsAddressTypeEnum::from_raw(
+                (*ptr).family as sys::virDomainEventGraphicsAddressType,
+            ),
+            node: c_chars_to_string!((*ptr).node, nofree),
+            service: if (*ptr).service.is_null() {
+                None
+            } else {
+                Some(c_chars_to_string!((*ptr).service, nofree))
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DomainEventGraphicsSubjectIdentity {
+    /// Type of identity (eg "x509dname", "saslUsername").
+    pub identity_type: String,
+    /// Identity value.
+    pub name: String,
+}
+
+impl DomainEventGraphicsSubjectIdentity {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    pub unsafe fn from_ptr(
+        ptr: *const sys::virDomainEventGraphicsSubjectIdentity,
+    ) -> DomainEventGraphicsSubjectIdentity {
+        DomainEventGraphicsSubjectIdentity {
+            identity_type: c_chars_to_string!((*ptr).type_, nofree),
+            name: c_chars_to_string!((*ptr).name, nofree),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainBlockJobType {
+    Unknown,
+    Pull,
+    Copy,
+    Commit,
+    ActiveCommit,
+    Backup,
+}
+
+pub type DomainBlockJobTypeEnum = Enum<DomainBlockJobType, sys::virDomainBlockJobType>;
+
+impl_enum! {
+    enum: DomainBlockJobType,
+    raw: sys::virDomainBlockJobType,
+    match: {
+    sys::VIR_DOMAIN_BLOCK_JOB_TYPE_UNKNOWN => Unknown,
+    sys::VIR_DOMAIN_BLOCK_JOB_TYPE_PULL => Pull,
+    sys::VIR_DOMAIN_BLOCK_JOB_TYPE_COPY => Copy,
+    sys::VIR_DOMAIN_BLOCK_JOB_TYPE_COMMIT => Commit,
+    sys::VIR_DOMAIN_BLOCK_JOB_TYPE_ACTIVE_COMMIT => ActiveCommit,
+    sys::VIR_DOMAIN_BLOCK_JOB_TYPE_BACKUP => Backup,
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::block_pull`].
+    struct BlockPullFlags: sys::virDomainBlockPullFlags {
+        /// The bandwidth is in bytes/s instead of MiB/s.
+        const BANDWIDTH_BYTES = sys::VIR_DOMAIN_BLOCK_PULL_BANDWIDTH_BYTES;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::block_rebase`].
+    struct BlockRebaseFlags: sys::virDomainBlockRebaseFlags {
+        /// Limit the copy to the top of the backing chain.
+        const SHALLOW = sys::VIR_DOMAIN_BLOCK_REBASE_SHALLOW;
+        /// Reuse the existing destination file.
+        const REUSE_EXT = sys::VIR_DOMAIN_BLOCK_REBASE_REUSE_EXT;
+        /// Use raw format for the destination.
+        const COPY_RAW = sys::VIR_DOMAIN_BLOCK_REBASE_COPY_RAW;
+        /// Start a copy job to the destination `base`.
+        const COPY = sys::VIR_DOMAIN_BLOCK_REBASE_COPY;
+        /// Keep the backing chain relative.
+        const RELATIVE = sys::VIR_DOMAIN_BLOCK_REBASE_RELATIVE;
+        /// The destination is a block device.
+        const COPY_DEV = sys::VIR_DOMAIN_BLOCK_REBASE_COPY_DEV;
+        /// The bandwidth is in bytes/s instead of MiB/s.
+        const BANDWIDTH_BYTES = sys::VIR_DOMAIN_BLOCK_REBASE_BANDWIDTH_BYTES;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::block_commit`].
+    struct BlockCommitFlags: sys::virDomainBlockCommitFlags {
+        /// Only commit `top` into its immediate backing image.
+        const SHALLOW = sys::VIR_DOMAIN_BLOCK_COMMIT_SHALLOW;
+        /// Delete the committed images once done.
+        const DELETE = sys::VIR_DOMAIN_BLOCK_COMMIT_DELETE;
+        /// Allow committing the active image.
+        const ACTIVE = sys::VIR_DOMAIN_BLOCK_COMMIT_ACTIVE;
+        /// Keep the backing chain relative.
+        const RELATIVE = sys::VIR_DOMAIN_BLOCK_COMMIT_RELATIVE;
+        /// The bandwidth is in bytes/s instead of MiB/s.
+        const BANDWIDTH_BYTES = sys::VIR_DOMAIN_BLOCK_COMMIT_BANDWIDTH_BYTES;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::block_copy`].
+    struct BlockCopyFlags: sys::virDomainBlockCopyFlags {
+        /// Limit the copy to the top of the backing chain.
+        const SHALLOW = sys::VIR_DOMAIN_BLOCK_COPY_SHALLOW;
+        /// Reuse the existing destination file.
+        const REUSE_EXT = sys::VIR_DOMAIN_BLOCK_COPY_REUSE_EXT;
+        /// Don't keep the job across a restart of the domain.
+        const TRANSIENT_JOB = sys::VIR_DOMAIN_BLOCK_COPY_TRANSIENT_JOB;
+        /// Let guest writes complete only once mirrored.
+        const SYNCHRONOUS_WRITES = sys::VIR_DOMAIN_BLOCK_COPY_SYNCHRONOUS_WRITES;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::block_job_info`].
+    struct BlockJobInfoFlags: sys::virDomainBlockJobInfoFlags {
+        /// Report the bandwidth in bytes/s instead of MiB/s.
+        const BANDWIDTH_BYTES = sys::VIR_DOMAIN_BLOCK_JOB_INFO_BANDWIDTH_BYTES;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::block_job_abort`].
+    struct BlockJobAbortFlags: sys::virDomainBlockJobAbortFlags {
+        /// Return without waiting for the job to be cancelled.
+        const ASYNC = sys::VIR_DOMAIN_BLOCK_JOB_ABORT_ASYNC;
+        /// Switch the disk to the copy instead of cancelling.
+        const PIVOT = sys::VIR_DOMAIN_BLOCK_JOB_ABORT_PIVOT;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::block_job_set_speed`].
+    struct BlockJobSetSpeedFlags: sys::virDomainBlockJobSetSpeedFlags {
+        /// The bandwidth is in bytes/s instead of MiB/s.
+        const BANDWIDTH_BYTES = sys::VIR_DOMAIN_BLOCK_JOB_SPEED_BANDWIDTH_BYTES;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::backup_begin`].
+    struct BackupBeginFlags: sys::virDomainBackupBeginFlags {
+        /// Reuse the existing files of a push mode backup target, or
+        /// of the scratch files of a pull mode backup.
+        const REUSE_EXTERNAL = sys::VIR_DOMAIN_BACKUP_BEGIN_REUSE_EXTERNAL;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainBlockJobStatus {
+    Completed,
+    Failed,
+    Canceled,
+    Ready,
+}
+
+pub type DomainBlockJobStatusEnum =
+    Enum<DomainBlockJobStatus, sys::virConnectDomainEventBlockJobStatus>;
+
+impl_enum! {
+    enum: DomainBlockJobStatus,
+    raw: sys::virConnectDomainEventBlockJobStatus,
+    match: {
+    sys::VIR_DOMAIN_BLOCK_JOB_COMPLETED => Completed,
+    sys::VIR_DOMAIN_BLOCK_JOB_FAILED => Failed,
+    sys::VIR_DOMAIN_BLOCK_JOB_CANCELED => Canceled,
+    sys::VIR_DOMAIN_BLOCK_JOB_READY => Ready,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventDiskChangeReason {
+    MissingOnStart,
+    DropMissingOnStart,
+}
+
+pub type DomainEventDiskChangeReasonEnum =
+    Enum<DomainEventDiskChangeReason, sys::virConnectDomainEventDiskChangeReason>;
+
+impl_enum! {
+    enum: DomainEventDiskChangeReason,
+    raw: sys::virConnectDomainEventDiskChangeReason,
+    match: {
+    sys::VIR_DOMAIN_EVENT_DISK_CHANGE_MISSING_ON_START => MissingOnStart,
+    sys::VIR_DOMAIN_EVENT_DISK_DROP_MISSING_ON_START => DropMissingOnStart,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventTrayChangeReason {
+    Open,
+    Close,
+}
+
+pub type DomainEventTrayChangeReasonEnum =
+    Enum<DomainEventTrayChangeReason, sys::virDomainEventTrayChangeReason>;
+
+impl_enum! {
+    enum: DomainEventTrayChangeReason,
+    raw: sys::virDomainEventTrayChangeReason,
+    match: {
+    sys::VIR_DOMAIN_EVENT_TRAY_CHANGE_OPEN => Open,
+    sys::VIR_DOMAIN_EVENT_TRAY_CHANGE_CLOSE => Close,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventAgentLifecycleState {
+    Connected,
+    Disconnected,
+}
+
+pub type DomainEventAgentLifecycleStateEnum =
+    Enum<DomainEventAgentLifecycleState, sys::virConnectDomainEventAgentLifecycleState>;
+
+impl_enum! {
+    enum: DomainEventAgentLifecycleState,
+    raw: sys::virConnectDomainEventAgentLifecycleState,
+    match: {
+    sys::VIR_CONNECT_DOMAIN_EVENT_AGENT_LIFECYCLE_STATE_CONNECTED => Connected,
+    sys::VIR_CONNECT_DOMAIN_EVENT_AGENT_LIFECYCLE_STATE_DISCONNECTED => Disconnected,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainEventAgentLifecycleReason {
+    Unknown,
+    DomainStarted,
+    Channel,
+}
+
+pub type DomainEventAgentLifecycleReasonEnum =
+    Enum<DomainEventAgentLifecycleReason, sys::virConnectDomainEventAgentLifecycleReason>;
+
+impl_enum! {
+    enum: DomainEventAgentLifecycleReason,
+    raw: sys::virConnectDomainEventAgentLifecycleReason,
+    match: {
+    sys::VIR_CONNECT_DOMAIN_EVENT_AGENT_LIFECYCLE_REASON_UNKNOWN => Unknown,
+    sys::VIR_CONNECT_DOMAIN_EVENT_AGENT_LIFECYCLE_REASON_DOMAIN_STARTED => DomainStarted,
+    sys::VIR_CONNECT_DOMAIN_EVENT_AGENT_LIFECYCLE_REASON_CHANNEL => Channel,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainMemoryFailureRecipient {
+    Hypervisor,
+    Guest,
+}
+
+pub type DomainMemoryFailureRecipientEnum =
+    Enum<DomainMemoryFailureRecipient, sys::virDomainMemoryFailureRecipientType>;
+
+impl_enum! {
+    enum: DomainMemoryFailureRecipient,
+    raw: sys::virDomainMemoryFailureRecipientType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_MEMORY_FAILURE_RECIPIENT_HYPERVISOR => Hypervisor,
+    sys::VIR_DOMAIN_EVENT_MEMORY_FAILURE_RECIPIENT_GUEST => Guest,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DomainMemoryFailureAction {
+    Ignore,
+    Inject,
+    Fatal,
+    Reset,
+}
+
+pub type DomainMemoryFailureActionEnum =
+    Enum<DomainMemoryFailureAction, sys::virDomainMemoryFailureActionType>;
+
+impl_enum! {
+    enum: DomainMemoryFailureAction,
+    raw: sys::virDomainMemoryFailureActionType,
+    match: {
+    sys::VIR_DOMAIN_EVENT_MEMORY_FAILURE_ACTION_IGNORE => Ignore,
+    sys::VIR_DOMAIN_EVENT_MEMORY_FAILURE_ACTION_INJECT => Inject,
+    sys::VIR_DOMAIN_EVENT_MEMORY_FAILURE_ACTION_FATAL => Fatal,
+    sys::VIR_DOMAIN_EVENT_MEMORY_FAILURE_ACTION_RESET => Reset,
+    }
+}
+
+/// Event delivered to a callback registered with
+/// [`Connect::domain_event_register_any`].
+///
+/// Each variant corresponds to one `VIR_DOMAIN_EVENT_ID_*` value.
+#[derive(Clone, Debug)]
+pub enum DomainEvent {
+    Lifecycle {
+        event: DomainEventTypeEnum,
+        detail: DomainEventDetailEnum,
+    },
+    Reboot,
+    RtcChange {
+        /// New offset from UTC, in seconds.
+        utc_offset: i64,
+    },
+    Watchdog {
+        action: DomainEventWatchdogActionEnum,
+    },
+    IOError {
+        src_path: String,
+        dev_alias: String,
+        action: DomainEventIOErrorActionEnum,
+    },
+    Graphics {
+        phase: DomainEventGraphicsPhaseEnum,
+        local: DomainEventGraphicsAddress,
+        remote: DomainEventGraphicsAddress,
+        auth_scheme: String,
+        subject: Vec<DomainEventGraphicsSubjectIdentity>,
+    },
+    IOErrorReason {
+        src_path: String,
+        dev_alias: String,
+        action: DomainEventIOErrorActionEnum,
+        reason: String,
+    },
+    ControlError,
+    BlockJob {
+        /// Fully-qualified path of the affected disk.
+        disk: String,
+        job_type: DomainBlockJobTypeEnum,
+        status: DomainBlockJobStatusEnum,
+    },
+    DiskChange {
+        old_src_path: Option<String>,
+        new_src_path: Option<String>,
+        dev_alias: String,
+        reason: DomainEventDiskChangeReasonEnum,
+    },
+    TrayChange {
+        dev_alias: String,
+        reason: DomainEventTrayChangeReasonEnum,
+    },
+    PMWakeup,
+    PMSuspend,
+    BalloonChange {
+        /// New balloon size in KiB.
+        actual: u64,
+    },
+    PMSuspendDisk,
+    DeviceRemoved {
+        dev_alias: String,
+    },
+    BlockJob2 {
+        /// Target name of the affected disk (eg "vda").
+        disk: String,
+        job_type: DomainBlockJobTypeEnum,
+        status: DomainBlockJobStatusEnum,
+    },
+    Tunable {
+        params: Vec<(String, TypedParamValue)>,
+    },
+    AgentLifecycle {
+        state: DomainEventAgentLifecycleStateEnum,
+        reason: DomainEventAgentLifecycleReasonEnum,
+    },
+    DeviceAdded {
+        dev_alias: String,
+    },
+    MigrationIteration {
+        iteration: i32,
+    },
+    JobCompleted {
+        stats: Box<JobStats>,
+    },
+    DeviceRemovalFailed {
+        dev_alias: String,
+    },
+    MetadataChange {
+        /// One of the `sys::VIR_DOMAIN_METADATA_*` constants.
+        metadata_type: i32,
+        nsuri: Option<String>,
+    },
+    BlockThreshold {
+        dev: String,
+        path: Option<String>,
+        threshold: u64,
+        excess: u64,
+    },
+    MemoryFailure {
+        recipient: DomainMemoryFailureRecipientEnum,
+        action: DomainMemoryFailureActionEnum,
+        /// Bitwise OR of `sys::VIR_DOMAIN_MEMORY_FAILURE_*` flags.
+        flags: u32,
+    },
+    MemoryDeviceSizeChange {
+        alias: String,
+        /// New current size of the device, in KiB.
+        size: u64,
+    },
+    NICMACChange {
+        alias: String,
+        old_mac: String,
+        new_mac: String,
+    },
+}
+
+impl DomainEvent {
+    pub(crate) fn lifecycle(event: libc::c_int, detail: libc::c_int) -> DomainEvent {
+        let event = DomainEventTypeEnum::from_raw(event as sys::virDomainEventType);
+        let detail = match event {
+            Enum::Known(k) => DomainEventDetailEnum::Known(match k {
+                DomainEventType::Defined => {
+                    DomainEventDetail::Defined(DomainEventDefinedDetailEnum::from_raw(
+                        detail as sys::virDomainEventDefinedDetailType,
+                    ))
+                }
+                DomainEventType::Undefined => {
+                    DomainEventDetail::Undefined(DomainEventUndefinedDetailEnum::from_raw(
+                        detail as sys::virDomainEventUndefinedDetailType,
+                    ))
+                }
+                DomainEventType::Started => {
+                    DomainEventDetail::Started(DomainEventStartedDetailEnum::from_raw(
+                        detail as sys::virDomainEventStartedDetailType,
+                    ))
+                }
+                DomainEventType::Suspended => {
+                    DomainEventDetail::Suspended(DomainEventSuspendedDetailEnum::from_raw(
+                        detail as sys::virDomainEventSuspendedDetailType,
+                    ))
+                }
+                DomainEventType::Resumed => {
+                    DomainEventDetail::Resumed(DomainEventResumedDetailEnum::from_raw(
+                        detail as sys::virDomainEventResumedDetailType,
+                    ))
+                }
+                DomainEventType::Stopped => {
+                    DomainEventDetail::Stopped(DomainEventStoppedDetailEnum::from_raw(
+                        detail as sys::virDomainEventStoppedDetailType,
+                    ))
+                }
+                DomainEventType::Shutdown => {
+                    DomainEventDetail::Shutdown(DomainEventShutdownDetailEnum::from_raw(
+                        detail as sys::virDomainEventShutdownDetailType,
+                    ))
+                }
+                DomainEventType::PMSuspended => {
+                    DomainEventDetail::PMSuspended(DomainEventPMSuspendedDetailEnum::from_raw(
+                        detail as sys::virDomainEventPMSuspendedDetailType,
+                    ))
+                }
+                DomainEventType::Crashed => {
+                    DomainEventDetail::Crashed(DomainEventCrashedDetailEnum::from_raw(
+                        detail as sys::virDomainEventCrashedDetailType,
+                    ))
+                }
+            }),
+            Enum::Unknown(_) => DomainEventDetailEnum::Unknown(detail),
+        };
+        DomainEvent::Lifecycle { event, detail }
+    }
+}
+
+unsafe fn optional_string(ptr: *const libc::c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(c_chars_to_string!(ptr, nofree))
+    }
+}
+
+unsafe extern "C" fn domain_event_lifecycle_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    event: libc::c_int,
+    detail: libc::c_int,
+    opaque: *mut libc::c_void,
+) -> libc::c_int {
+    let event = || DomainEvent::lifecycle(event, detail);
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+    0
+}
+
+unsafe extern "C" fn domain_event_reboot_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, || DomainEvent::Reboot);
+}
+
+unsafe extern "C" fn domain_event_control_error_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, || {
+        DomainEvent::ControlError
+    });
+}
+
+unsafe extern "C" fn domain_event_rtc_change_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    utc_offset: libc::c_longlong,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::RtcChange { utc_offset };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_watchdog_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    action: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::Watchdog {
+        action: DomainEventWatchdogActionEnum::from_raw(
+            action as sys::virDomainEventWatchdogAction,
+        ),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_io_error_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    src_path: *const libc::c_char,
+    dev_alias: *const libc::c_char,
+    action: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::IOError {
+        src_path: c_chars_to_string!(src_path, nofree),
+        dev_alias: c_chars_to_string!(dev_alias, nofree),
+        action: DomainEventIOErrorActionEnum::from_raw(action as sys::virDomainEventIOErrorAction),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_io_error_reason_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    src_path: *const libc::c_char,
+    dev_alias: *const libc::c_char,
+    action: libc::c_int,
+    reason: *const libc::c_char,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::IOErrorReason {
+        src_path: c_chars_to_string!(src_path, nofree),
+        dev_alias: c_chars_to_string!(dev_alias, nofree),
+        action: DomainEventIOErrorActionEnum::from_raw(action as sys::virDomainEventIOErrorAction),
+        reason: c_chars_to_string!(reason, nofree),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_graphics_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    phase: libc::c_int,
+    local: *const sys::virDomainEventGraphicsAddress,
+    remote: *const sys::virDomainEventGraphicsAddress,
+    auth_scheme: *const libc::c_char,
+    subject: *const sys::virDomainEventGraphicsSubject,
+    opaque: *mut libc::c_void,
+) {
+    let event = || {
+        let mut identities = Vec::new();
+        for i in 0..(*subject).nidentity as isize {
+            identities.push(DomainEventGraphicsSubjectIdentity::from_ptr(
+                (*subject).identities.offset(i),
+            ));
+        }
+        DomainEvent::Graphics {
+            phase: DomainEventGraphicsPhaseEnum::from_raw(
+                phase as sys::virDomainEventGraphicsPhase,
+            ),
+            local: DomainEventGraphicsAddress::from_ptr(local),
+            remote: DomainEventGraphicsAddress::from_ptr(remote),
+            auth_scheme: c_chars_to_string!(auth_scheme, nofree),
+            subject: identities,
+        }
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_block_job_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    disk: *const libc::c_char,
+    job_type: libc::c_int,
+    status: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::BlockJob {
+        disk: c_chars_to_string!(disk, nofree),
+        job_type: DomainBlockJobTypeEnum::from_raw(job_type as sys::virDomainBlockJobType),
+        status: DomainBlockJobStatusEnum::from_raw(
+            status as sys::virConnectDomainEventBlockJobStatus,
+        ),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_block_job_2_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    disk: *const libc::c_char,
+    job_type: libc::c_int,
+    status: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::BlockJob2 {
+        disk: c_chars_to_string!(disk, nofree),
+        job_type: DomainBlockJobTypeEnum::from_raw(job_type as sys::virDomainBlockJobType),
+        status: DomainBlockJobStatusEnum::from_raw(
+            status as sys::virConnectDomainEventBlockJobStatus,
+        ),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_disk_change_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    old_src_path: *const libc::c_char,
+    new_src_path: *const libc::c_char,
+    dev_alias: *const libc::c_char,
+    reason: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::DiskChange {
+        old_src_path: optional_string(old_src_path),
+        new_src_path: optional_string(new_src_path),
+        dev_alias: c_chars_to_string!(dev_alias, nofree),
+        reason: DomainEventDiskChangeReasonEnum::from_raw(
+            reason as sys::virConnectDomainEventDiskChangeReason,
+        ),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_tray_change_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    dev_alias: *const libc::c_char,
+    reason: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::TrayChange {
+        dev_alias: c_chars_to_string!(dev_alias, nofree),
+        reason: DomainEventTrayChangeReasonEnum::from_raw(
+            reason as sys::virDomainEventTrayChangeReason,
+        ),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_pm_wakeup_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    _reason: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, || {
+        DomainEvent::PMWakeup
+    });
+}
+
+unsafe extern "C" fn domain_event_pm_suspend_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    _reason: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, || {
+        DomainEvent::PMSuspend
+    });
+}
+
+unsafe extern "C" fn domain_event_pm_suspend_disk_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    _reason: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, || {
+        DomainEvent::PMSuspendDisk
+    });
+}
+
+unsafe extern "C" fn domain_event_balloon_change_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    actual: libc::c_ulonglong,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::BalloonChange { actual };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_device_removed_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    dev_alias: *const libc::c_char,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::DeviceRemoved {
+        dev_alias: c_chars_to_string!(dev_alias, nofree),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_device_added_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    dev_alias: *const libc::c_char,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::DeviceAdded {
+        dev_alias: c_chars_to_string!(dev_alias, nofree),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_device_removal_failed_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    dev_alias: *const libc::c_char,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::DeviceRemovalFailed {
+        dev_alias: c_chars_to_string!(dev_alias, nofree),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_tunable_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    params: sys::virTypedParameterPtr,
+    nparams: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || {
+        let params = slice::from_raw_parts(params, nparams as usize);
+        DomainEvent::Tunable {
+            params: from_params_generic(params),
+        }
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_agent_lifecycle_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    state: libc::c_int,
+    reason: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::AgentLifecycle {
+        state: DomainEventAgentLifecycleStateEnum::from_raw(
+            state as sys::virConnectDomainEventAgentLifecycleState,
+        ),
+        reason: DomainEventAgentLifecycleReasonEnum::from_raw(
+            reason as sys::virConnectDomainEventAgentLifecycleReason,
+        ),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_migration_iteration_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    iteration: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::MigrationIteration { iteration };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_job_completed_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    params: sys::virTypedParameterPtr,
+    nparams: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || {
+        // The parameters are owned by libvirt, only copy the values out.
+        let params = slice::from_raw_parts(params, nparams as usize).to_vec();
+        DomainEvent::JobCompleted {
+            stats: Box::new((sys::VIR_DOMAIN_JOB_COMPLETED as i32, params).into()),
+        }
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_metadata_change_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    metadata_type: libc::c_int,
+    nsuri: *const libc::c_char,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::MetadataChange {
+        metadata_type,
+        nsuri: optional_string(nsuri),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_block_threshold_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    dev: *const libc::c_char,
+    path: *const libc::c_char,
+    threshold: libc::c_ulonglong,
+    excess: libc::c_ulonglong,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::BlockThreshold {
+        dev: c_chars_to_string!(dev, nofree),
+        path: optional_string(path),
+        threshold,
+        excess,
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+unsafe extern "C" fn domain_event_memory_failure_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    recipient: libc::c_int,
+    action: libc::c_int,
+    flags: libc::c_uint,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::MemoryFailure {
+        recipient: DomainMemoryFailureRecipientEnum::from_raw(
+            recipient as sys::virDomainMemoryFailureRecipientType,
+        ),
+        action: DomainMemoryFailureActionEnum::from_raw(
+            action as sys::virDomainMemoryFailureActionType,
+        ),
+        flags,
     };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
 }
 
-impl SchedulerInfo {
-    pub fn from_vec(vec: Vec<sys::virTypedParameter>, scheduler_type: String) -> SchedulerInfo {
-        let mut ret = SchedulerInfo {
-            scheduler_type,
-            ..Default::default()
-        };
-        let fields = scheduler_info_fields!(param_field_in, ret);
-        from_params(vec, fields);
-        ret
-    }
+unsafe extern "C" fn domain_event_memory_device_size_change_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    alias: *const libc::c_char,
+    size: libc::c_ulonglong,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::MemoryDeviceSizeChange {
+        alias: c_chars_to_string!(alias, nofree),
+        size,
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
 
-    pub fn to_vec(&self) -> Vec<sys::virTypedParameter> {
-        let fields = scheduler_info_fields!(param_field_out, self);
-        to_params(fields)
+unsafe extern "C" fn domain_event_nic_mac_change_callback<
+    F: FnMut(&Connect, &Domain, DomainEvent),
+>(
+    conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    alias: *const libc::c_char,
+    old_mac: *const libc::c_char,
+    new_mac: *const libc::c_char,
+    opaque: *mut libc::c_void,
+) {
+    let event = || DomainEvent::NICMACChange {
+        alias: c_chars_to_string!(alias, nofree),
+        old_mac: c_chars_to_string!(old_mac, nofree),
+        new_mac: c_chars_to_string!(new_mac, nofree),
+    };
+    event_dispatch::<_, _, F>(conn, Domain::from_ptr(dom), opaque, event);
+}
+
+/// Returns the C callback matching the signature libvirt uses for
+/// `event_id`, cast to the generic callback type expected by
+/// virConnectDomainEventRegisterAny.
+pub(crate) fn domain_event_callback<F: FnMut(&Connect, &Domain, DomainEvent)>(
+    event_id: DomainEventId,
+) -> sys::virConnectDomainEventGenericCallback {
+    // Safe because libvirt casts the generic callback back to the
+    // signature matching event_id before invoking it.
+    unsafe {
+        match event_id {
+            DomainEventId::Lifecycle => mem::transmute::<
+                sys::virConnectDomainEventCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_lifecycle_callback::<F>)),
+            DomainEventId::Reboot => Some(domain_event_reboot_callback::<F>),
+            DomainEventId::RtcChange => mem::transmute::<
+                sys::virConnectDomainEventRTCChangeCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_rtc_change_callback::<F>)),
+            DomainEventId::Watchdog => mem::transmute::<
+                sys::virConnectDomainEventWatchdogCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_watchdog_callback::<F>)),
+            DomainEventId::IOError => mem::transmute::<
+                sys::virConnectDomainEventIOErrorCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_io_error_callback::<F>)),
+            DomainEventId::Graphics => mem::transmute::<
+                sys::virConnectDomainEventGraphicsCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_graphics_callback::<F>)),
+            DomainEventId::IOErrorReason => {
+                mem::transmute::<
+                    sys::virConnectDomainEventIOErrorReasonCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_io_error_reason_callback::<F>))
+            }
+            DomainEventId::ControlError => Some(domain_event_control_error_callback::<F>),
+            DomainEventId::BlockJob => mem::transmute::<
+                sys::virConnectDomainEventBlockJobCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_block_job_callback::<F>)),
+            DomainEventId::DiskChange => {
+                mem::transmute::<
+                    sys::virConnectDomainEventDiskChangeCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_disk_change_callback::<F>))
+            }
+            DomainEventId::TrayChange => {
+                mem::transmute::<
+                    sys::virConnectDomainEventTrayChangeCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_tray_change_callback::<F>))
+            }
+            DomainEventId::PMWakeup => mem::transmute::<
+                sys::virConnectDomainEventPMWakeupCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_pm_wakeup_callback::<F>)),
+            DomainEventId::PMSuspend => mem::transmute::<
+                sys::virConnectDomainEventPMSuspendCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_pm_suspend_callback::<F>)),
+            DomainEventId::BalloonChange => {
+                mem::transmute::<
+                    sys::virConnectDomainEventBalloonChangeCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_balloon_change_callback::<F>))
+            }
+            DomainEventId::PMSuspendDisk => {
+                mem::transmute::<
+                    sys::virConnectDomainEventPMSuspendDiskCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_pm_suspend_disk_callback::<F>))
+            }
+            DomainEventId::DeviceRemoved => {
+                mem::transmute::<
+                    sys::virConnectDomainEventDeviceRemovedCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_device_removed_callback::<F>))
+            }
+            DomainEventId::BlockJob2 => mem::transmute::<
+                sys::virConnectDomainEventBlockJobCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_block_job_2_callback::<F>)),
+            DomainEventId::Tunable => mem::transmute::<
+                sys::virConnectDomainEventTunableCallback,
+                sys::virConnectDomainEventGenericCallback,
+            >(Some(domain_event_tunable_callback::<F>)),
+            DomainEventId::AgentLifecycle => {
+                mem::transmute::<
+                    sys::virConnectDomainEventAgentLifecycleCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_agent_lifecycle_callback::<F>))
+            }
+            DomainEventId::DeviceAdded => {
+                mem::transmute::<
+                    sys::virConnectDomainEventDeviceAddedCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_device_added_callback::<F>))
+            }
+            DomainEventId::MigrationIteration => {
+                mem::transmute::<
+                    sys::virConnectDomainEventMigrationIterationCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_migration_iteration_callback::<F>))
+            }
+            DomainEventId::JobCompleted => {
+                mem::transmute::<
+                    sys::virConnectDomainEventJobCompletedCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_job_completed_callback::<F>))
+            }
+            DomainEventId::DeviceRemovalFailed => {
+                mem::transmute::<
+                    sys::virConnectDomainEventDeviceRemovalFailedCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_device_removal_failed_callback::<F>))
+            }
+            DomainEventId::MetadataChange => {
+                mem::transmute::<
+                    sys::virConnectDomainEventMetadataChangeCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_metadata_change_callback::<F>))
+            }
+            DomainEventId::BlockThreshold => {
+                mem::transmute::<
+                    sys::virConnectDomainEventBlockThresholdCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_block_threshold_callback::<F>))
+            }
+            DomainEventId::MemoryFailure => {
+                mem::transmute::<
+                    sys::virConnectDomainEventMemoryFailureCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_memory_failure_callback::<F>))
+            }
+            DomainEventId::MemoryDeviceSizeChange => {
+                mem::transmute::<
+                    sys::virConnectDomainEventMemoryDeviceSizeChangeCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_memory_device_size_change_callback::<F>))
+            }
+            DomainEventId::NICMACChange => {
+                mem::transmute::<
+                    sys::virConnectDomainEventNICMACChangeCallback,
+                    sys::virConnectDomainEventGenericCallback,
+                >(Some(domain_event_nic_mac_change_callback::<F>))
+            }
+        }
     }
 }
 
+/// Registration of a domain event callback, returned by
+/// [`Connect::domain_event_register_any`].
+pub type DomainEventCallback = EventCallback;
+
 /// Provides APIs for the management of domains.
 ///
 /// See <https://libvirt.org/html/libvirt-libvirt-domain.html>
@@ -1088,39 +4434,33 @@ impl Domain {
         let _ = check_neg!(unsafe {
             sys::virDomainGetState(self.as_ptr(), &mut state, &mut reason, 0)
         });
-        let state = DomainStateEnum::from_raw(state as sys::virDomainState);
-        let reason = match state {
-            Enum::Known(k) => DomainStateReasonEnum::Known(match k {
-                DomainState::NoState => DomainStateReason::NoState(
-                    DomainNoStateReasonEnum::from_raw(reason as sys::virDomainNostateReason),
-                ),
-                DomainState::Running => DomainStateReason::Running(
-                    DomainRunningReasonEnum::from_raw(reason as sys::virDomainRunningReason),
-                ),
-                DomainState::Blocked => DomainStateReason::Blocked(
-                    DomainBlockedReasonEnum::from_raw(reason as sys::virDomainBlockedReason),
-                ),
-                DomainState::Paused => DomainStateReason::Paused(DomainPausedReasonEnum::from_raw(
-                    reason as sys::virDomainPausedReason,
-                )),
-                DomainState::Shutdown => DomainStateReason::Shutdown(
-                    DomainShutdownReasonEnum::from_raw(reason as sys::virDomainShutdownReason),
-                ),
-                DomainState::Shutoff => DomainStateReason::Shutoff(
-                    DomainShutoffReasonEnum::from_raw(reason as sys::virDomainShutoffReason),
-                ),
-                DomainState::Crashed => DomainStateReason::Crashed(
-                    DomainCrashedReasonEnum::from_raw(reason as sys::virDomainCrashedReason),
-                ),
-                DomainState::PMSuspended => {
-                    DomainStateReason::PMSuspended(DomainPMSuspendedReasonEnum::from_raw(
-                        reason as sys::virDomainPMSuspendedReason,
-                    ))
-                }
-            }),
-            Enum::Unknown(_) => DomainStateReasonEnum::Unknown(reason),
-        };
-        Ok((state, reason))
+        Ok(state_from_raw(state, reason))
+    }
+
+    /// Returns the statistics of the given domains, parsed in the
+    /// groups selected by `stats`.
+    ///
+    /// All domains must belong to the same connection.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainListGetStats>
+    pub fn list_get_stats(
+        doms: &[Domain],
+        stats: DomainStatsTypes,
+        flags: DomainStatsFlags,
+    ) -> Result<Vec<DomainStatsRecord>, Error> {
+        // The list of domains is terminated by a NULL pointer.
+        let mut ptrs: Vec<sys::virDomainPtr> = doms.iter().map(|d| d.ptr).collect();
+        ptrs.push(ptr::null_mut());
+        let mut record: *mut sys::virDomainStatsRecordPtr = ptr::null_mut();
+        let size = check_neg!(unsafe {
+            sys::virDomainListGetStats(
+                ptrs.as_mut_ptr(),
+                stats.to_raw() as libc::c_uint,
+                &mut record,
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+        Ok(unsafe { DomainStatsRecord::from_list(record, size as usize) })
     }
 
     /// Get the public name of the domain.
@@ -1529,6 +4869,82 @@ impl Domain {
         Ok(())
     }
 
+    /// Returns the state of the vCPUs inside the guest, through the
+    /// guest agent
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetGuestVcpus>
+    pub fn guest_vcpus(&self, flags: u32) -> Result<GuestVcpus, Error> {
+        let mut nparams: libc::c_uint = 0;
+        let mut params: sys::virTypedParameterPtr = ptr::null_mut();
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetGuestVcpus(
+                self.as_ptr(),
+                &mut params,
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        let res = unsafe { slice::from_raw_parts(params, nparams as usize) }.to_vec();
+        let vcpus = GuestVcpus::from_vec(res);
+        unsafe { sys::virTypedParamsFree(params, nparams as libc::c_int) };
+        vcpus
+    }
+
+    /// Onlines or offlines vCPUs inside the guest, through the guest
+    /// agent
+    ///
+    /// `cpumap` must not be empty.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetGuestVcpus>
+    pub fn set_guest_vcpus(&self, cpumap: &CpuMap, state: bool, flags: u32) -> Result<(), Error> {
+        if cpumap.is_empty() {
+            return Err(Error::new(
+                ErrorNumber::InvalidArg,
+                "no vCPU selected".into(),
+            ));
+        }
+        let cpumap_buf = CString::new(cpumap.to_string())?;
+        let _ = check_neg!(unsafe {
+            sys::virDomainSetGuestVcpus(
+                self.as_ptr(),
+                cpumap_buf.as_ptr(),
+                state as libc::c_int,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Hot(un)plugs individual vCPUs of the domain
+    ///
+    /// Unlike [`Domain::set_vcpus_flags`], this selects which vCPUs
+    /// are enabled or disabled. `vcpumap` must not be empty.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetVcpu>
+    pub fn set_vcpu(
+        &self,
+        vcpumap: &CpuMap,
+        state: bool,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
+        if vcpumap.is_empty() {
+            return Err(Error::new(
+                ErrorNumber::InvalidArg,
+                "no vCPU selected".into(),
+            ));
+        }
+        let vcpumap_buf = CString::new(vcpumap.to_string())?;
+        let _ = check_neg!(unsafe {
+            sys::virDomainSetVcpu(
+                self.as_ptr(),
+                vcpumap_buf.as_ptr(),
+                state as libc::c_int,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
     /// Returns the domani vCPU count
     ///
     /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetVcpusFlags>
@@ -1614,105 +5030,288 @@ impl Domain {
         let _ = check_neg!(unsafe {
             sys::virDomainSetTime(
                 self.as_ptr(),
-                seconds as libc::c_longlong,
-                nseconds as libc::c_uint,
+                seconds as libc::c_longlong,
+                nseconds as libc::c_uint,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the domain virtual clock
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetTime>
+    pub fn time(&self, flags: u32) -> Result<(i64, i32), Error> {
+        let mut seconds: libc::c_longlong = 0;
+        let mut nseconds: libc::c_uint = 0;
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetTime(
+                self.as_ptr(),
+                &mut seconds,
+                &mut nseconds,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok((seconds, nseconds as i32))
+    }
+
+    /// Returns information for a domain block device
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetBlockInfo>
+    pub fn block_info(&self, disk: &str, flags: u32) -> Result<BlockInfo, Error> {
+        let mut pinfo = mem::MaybeUninit::uninit();
+        let disk_buf = CString::new(disk)?;
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetBlockInfo(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                pinfo.as_mut_ptr(),
+                flags as libc::c_uint,
+            )
+        });
+        Ok(unsafe { BlockInfo::from_ptr(&mut pinfo.assume_init()) })
+    }
+
+    /// Returns I/O statistics for a domain block device
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockStats>
+    pub fn block_stats(&self, disk: &str) -> Result<BlockStats, Error> {
+        let mut pinfo = mem::MaybeUninit::uninit();
+        let disk_buf = CString::new(disk)?;
+        let _ = check_neg!(unsafe {
+            sys::virDomainBlockStats(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                pinfo.as_mut_ptr(),
+                mem::size_of::<sys::virDomainBlockStatsStruct>(),
+            )
+        })?;
+        Ok(unsafe { BlockStats::from_ptr(&mut pinfo.assume_init()) })
+    }
+
+    /// Pin a domain vCPU to host pCPUs
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainPinVcpu>
+    pub fn pin_vcpu(&self, vcpu: u32, cpumap: &[u8]) -> Result<(), Error> {
+        let _ = check_neg!(unsafe {
+            sys::virDomainPinVcpu(
+                self.as_ptr(),
+                vcpu as libc::c_uint,
+                cpumap.as_ptr() as *mut _,
+                cpumap.len() as libc::c_int,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Pin a domain vCPU to host pCPUs
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainPinVcpuFlags>
+    pub fn pin_vcpu_flags(&self, vcpu: u32, cpumap: &[u8], flags: u32) -> Result<(), Error> {
+        let _ = check_neg!(unsafe {
+            sys::virDomainPinVcpuFlags(
+                self.as_ptr(),
+                vcpu as libc::c_uint,
+                cpumap.as_ptr() as *mut _,
+                cpumap.len() as libc::c_int,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Pin the domain emulator threads to host pCPUs
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainPinEmulator>
+    pub fn pin_emulator(&self, cpumap: &[u8], flags: u32) -> Result<(), Error> {
+        let _ = check_neg!(unsafe {
+            sys::virDomainPinEmulator(
+                self.as_ptr(),
+                cpumap.as_ptr() as *mut _,
+                cpumap.len() as libc::c_int,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the state, usage and affinity of the vCPUs of a running
+    /// domain
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetVcpus>
+    pub fn vcpus(&self) -> Result<Vec<VcpuInfo>, Error> {
+        let maxinfo = self.info()?.nr_virt_cpu as usize;
+        let maplen = CpuMap::for_node(&self.connect()?.node_info()?)
+            .as_bytes()
+            .len();
+        let mut info: Vec<sys::virVcpuInfo> = Vec::with_capacity(maxinfo);
+        let mut cpumaps: Vec<u8> = vec![0; maxinfo * maplen];
+        let ret = check_neg!(unsafe {
+            sys::virDomainGetVcpus(
+                self.as_ptr(),
+                info.as_mut_ptr(),
+                maxinfo as libc::c_int,
+                cpumaps.as_mut_ptr(),
+                maplen as libc::c_int,
+            )
+        })?;
+        unsafe { info.set_len(ret as usize) };
+        let affinity = split_cpumaps(&cpumaps, maplen, info.len());
+        Ok(info
+            .into_iter()
+            .zip(affinity)
+            .map(|(info, affinity)| VcpuInfo {
+                number: info.number,
+                state: VcpuStateEnum::from_raw(info.state as sys::virVcpuState),
+                cpu_time: info.cpuTime,
+                cpu: info.cpu,
+                affinity,
+            })
+            .collect())
+    }
+
+    /// Returns the host CPUs each vCPU is pinned to, indexed by vCPU
+    /// number
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetVcpuPinInfo>
+    pub fn vcpu_pin_info(
+        &self,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<Vec<CpuMap>, Error> {
+        let ncpumaps = self.vcpus_flags(flags | sys::VIR_DOMAIN_VCPU_MAXIMUM)? as usize;
+        let maplen = CpuMap::for_node(&self.connect()?.node_info()?)
+            .as_bytes()
+            .len();
+        let mut cpumaps: Vec<u8> = vec![0; ncpumaps * maplen];
+        let ret = check_neg!(unsafe {
+            sys::virDomainGetVcpuPinInfo(
+                self.as_ptr(),
+                ncpumaps as libc::c_int,
+                cpumaps.as_mut_ptr(),
+                maplen as libc::c_int,
                 flags as libc::c_uint,
             )
         })?;
-        Ok(())
+        Ok(split_cpumaps(&cpumaps, maplen, ret as usize))
     }
 
-    /// Returns the domain virtual clock
+    /// Returns the host CPUs the domain emulator threads are pinned to
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetTime>
-    pub fn time(&self, flags: u32) -> Result<(i64, i32), Error> {
-        let mut seconds: libc::c_longlong = 0;
-        let mut nseconds: libc::c_uint = 0;
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetEmulatorPinInfo>
+    pub fn emulator_pin_info(
+        &self,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<CpuMap, Error> {
+        let mut cpumap = CpuMap::for_node(&self.connect()?.node_info()?);
         let _ = check_neg!(unsafe {
-            sys::virDomainGetTime(
+            sys::virDomainGetEmulatorPinInfo(
                 self.as_ptr(),
-                &mut seconds,
-                &mut nseconds,
+                cpumap.bytes.as_mut_ptr(),
+                cpumap.bytes.len() as libc::c_int,
                 flags as libc::c_uint,
             )
         })?;
-        Ok((seconds, nseconds as i32))
+        Ok(cpumap)
     }
 
-    /// Returns information for a domain block device
+    /// Returns the IOThreads of the domain and their CPU affinity
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetBlockInfo>
-    pub fn block_info(&self, disk: &str, flags: u32) -> Result<BlockInfo, Error> {
-        let mut pinfo = mem::MaybeUninit::uninit();
-        let disk_buf = CString::new(disk)?;
-        let _ = check_neg!(unsafe {
-            sys::virDomainGetBlockInfo(
-                self.as_ptr(),
-                disk_buf.as_ptr(),
-                pinfo.as_mut_ptr(),
-                flags as libc::c_uint,
-            )
-        });
-        Ok(unsafe { BlockInfo::from_ptr(&mut pinfo.assume_init()) })
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetIOThreadInfo>
+    pub fn iothread_info(
+        &self,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<Vec<IOThreadInfo>, Error> {
+        let mut info: *mut sys::virDomainIOThreadInfoPtr = ptr::null_mut();
+        let size = check_neg!(unsafe {
+            sys::virDomainGetIOThreadInfo(self.as_ptr(), &mut info, flags as libc::c_uint)
+        })?;
+
+        let mut array: Vec<IOThreadInfo> = Vec::new();
+        for x in 0..size as isize {
+            unsafe {
+                let ptr = *info.offset(x);
+                array.push(IOThreadInfo::from_ptr(ptr));
+                sys::virDomainIOThreadInfoFree(ptr);
+            }
+        }
+        unsafe { libc::free(info as *mut libc::c_void) };
+
+        Ok(array)
     }
 
-    /// Returns I/O statistics for a domain block device
+    /// Pin an IOThread to host pCPUs
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockStats>
-    pub fn block_stats(&self, disk: &str) -> Result<BlockStats, Error> {
-        let mut pinfo = mem::MaybeUninit::uninit();
-        let disk_buf = CString::new(disk)?;
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainPinIOThread>
+    pub fn pin_iothread(
+        &self,
+        iothread_id: u32,
+        cpumap: &CpuMap,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
         let _ = check_neg!(unsafe {
-            sys::virDomainBlockStats(
+            sys::virDomainPinIOThread(
                 self.as_ptr(),
-                disk_buf.as_ptr(),
-                pinfo.as_mut_ptr(),
-                mem::size_of::<sys::virDomainBlockStatsStruct>(),
+                iothread_id as libc::c_uint,
+                cpumap.as_bytes().as_ptr() as *mut _,
+                cpumap.as_bytes().len() as libc::c_int,
+                flags as libc::c_uint,
             )
         })?;
-        Ok(unsafe { BlockStats::from_ptr(&mut pinfo.assume_init()) })
+        Ok(())
     }
 
-    /// Pin a domain vCPU to host pCPUs
+    /// Adds an IOThread to the domain
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainPinVcpu>
-    pub fn pin_vcpu(&self, vcpu: u32, cpumap: &[u8]) -> Result<(), Error> {
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainAddIOThread>
+    pub fn add_iothread(
+        &self,
+        iothread_id: u32,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
         let _ = check_neg!(unsafe {
-            sys::virDomainPinVcpu(
+            sys::virDomainAddIOThread(
                 self.as_ptr(),
-                vcpu as libc::c_uint,
-                cpumap.as_ptr() as *mut _,
-                cpumap.len() as libc::c_int,
+                iothread_id as libc::c_uint,
+                flags as libc::c_uint,
             )
         })?;
         Ok(())
     }
 
-    /// Pin a domain vCPU to host pCPUs
+    /// Removes an IOThread from the domain
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainPinVcpuFlags>
-    pub fn pin_vcpu_flags(&self, vcpu: u32, cpumap: &[u8], flags: u32) -> Result<(), Error> {
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainDelIOThread>
+    pub fn del_iothread(
+        &self,
+        iothread_id: u32,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
         let _ = check_neg!(unsafe {
-            sys::virDomainPinVcpuFlags(
+            sys::virDomainDelIOThread(
                 self.as_ptr(),
-                vcpu as libc::c_uint,
-                cpumap.as_ptr() as *mut _,
-                cpumap.len() as libc::c_int,
+                iothread_id as libc::c_uint,
                 flags as libc::c_uint,
             )
         })?;
         Ok(())
     }
 
-    /// Pin the domain emulator threads to host pCPUs
+    /// Updates the polling and thread pool parameters of an IOThread
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainPinEmulator>
-    pub fn pin_emulator(&self, cpumap: &[u8], flags: u32) -> Result<(), Error> {
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetIOThreadParams>
+    pub fn set_iothread_params(
+        &self,
+        iothread_id: u32,
+        params: IOThreadParameters,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
+        let mut cparams = params.to_vec();
         let _ = check_neg!(unsafe {
-            sys::virDomainPinEmulator(
+            sys::virDomainSetIOThreadParams(
                 self.as_ptr(),
-                cpumap.as_ptr() as *mut _,
-                cpumap.len() as libc::c_int,
+                iothread_id as libc::c_uint,
+                cparams.as_mut_ptr(),
+                cparams.len() as libc::c_int,
                 flags as libc::c_uint,
             )
         })?;
@@ -1985,6 +5584,209 @@ impl Domain {
         Ok((r#type, res).into())
     }
 
+    /// Freezes the guest filesystems through the guest agent, or all
+    /// of them when `mountpoints` is empty
+    ///
+    /// All the filesystems are thawed when the returned guard is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn snapshot(dom: &virt::domain::Domain) -> Result<(), virt::error::Error> {
+    /// let frozen = dom.fs_freeze(&[], 0)?;
+    /// dom.create_snapshot_xml("<domainsnapshot/>", virt::sys::VIR_DOMAIN_SNAPSHOT_CREATE_DISK_ONLY)?;
+    /// frozen.thaw()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainFSFreeze>
+    pub fn fs_freeze(&self, mountpoints: &[&str], flags: u32) -> Result<FSFreezeGuard, Error> {
+        let mountpoint_bufs = mountpoints
+            .iter()
+            .map(|m| CString::new(*m))
+            .collect::<Result<Vec<CString>, _>>()?;
+        let mut ptrs: Vec<*const libc::c_char> =
+            mountpoint_bufs.iter().map(|m| m.as_ptr()).collect();
+        let ret = check_neg!(unsafe {
+            sys::virDomainFSFreeze(
+                self.as_ptr(),
+                if ptrs.is_empty() {
+                    ptr::null_mut()
+                } else {
+                    ptrs.as_mut_ptr()
+                },
+                ptrs.len() as libc::c_uint,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(FSFreezeGuard {
+            domain: self.clone(),
+            frozen: ret as u32,
+            thawed: false,
+        })
+    }
+
+    /// Thaws the guest filesystems through the guest agent, or all of
+    /// them when `mountpoints` is empty, returning the number of
+    /// thawed filesystems
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainFSThaw>
+    pub fn fs_thaw(&self, mountpoints: &[&str], flags: u32) -> Result<u32, Error> {
+        let mountpoint_bufs = mountpoints
+            .iter()
+            .map(|m| CString::new(*m))
+            .collect::<Result<Vec<CString>, _>>()?;
+        let mut ptrs: Vec<*const libc::c_char> =
+            mountpoint_bufs.iter().map(|m| m.as_ptr()).collect();
+        let ret = check_neg!(unsafe {
+            sys::virDomainFSThaw(
+                self.as_ptr(),
+                if ptrs.is_empty() {
+                    ptr::null_mut()
+                } else {
+                    ptrs.as_mut_ptr()
+                },
+                ptrs.len() as libc::c_uint,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(ret as u32)
+    }
+
+    /// Discards the unused blocks of a guest filesystem, or of all of
+    /// them when `mountpoint` is `None`
+    ///
+    /// Free ranges smaller than `minimum` bytes may be ignored.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainFSTrim>
+    pub fn fs_trim(&self, mountpoint: Option<&str>, minimum: u64, flags: u32) -> Result<(), Error> {
+        let mountpoint_buf = some_string_to_cstring!(mountpoint);
+        let _ = check_neg!(unsafe {
+            sys::virDomainFSTrim(
+                self.as_ptr(),
+                some_cstring_to_c_chars!(mountpoint_buf),
+                minimum as libc::c_ulonglong,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the filesystems mounted in the guest
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetFSInfo>
+    pub fn get_fs_info(&self, flags: u32) -> Result<Vec<FSInfo>, Error> {
+        let mut info: *mut sys::virDomainFSInfoPtr = ptr::null_mut();
+        let size = check_neg!(unsafe {
+            sys::virDomainGetFSInfo(self.as_ptr(), &mut info, flags as libc::c_uint)
+        })?;
+
+        let mut array: Vec<FSInfo> = Vec::new();
+        for x in 0..size as isize {
+            unsafe {
+                let ptr = *info.offset(x);
+                array.push(FSInfo::from_ptr(ptr));
+                sys::virDomainFSInfoFree(ptr);
+            }
+        }
+        unsafe { libc::free(info as *mut libc::c_void) };
+
+        Ok(array)
+    }
+
+    /// Returns information about the guest, gathered by the guest
+    /// agent
+    ///
+    /// `types` selects the groups to query, 0 queries all of them.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetGuestInfo>
+    pub fn guest_info(
+        &self,
+        types: sys::virDomainGuestInfoTypes,
+        flags: u32,
+    ) -> Result<GuestInfo, Error> {
+        let mut nparams: libc::c_int = 0;
+        let mut params: sys::virTypedParameterPtr = ptr::null_mut();
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetGuestInfo(
+                self.as_ptr(),
+                types as libc::c_uint,
+                &mut params,
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        let res = unsafe { slice::from_raw_parts(params, nparams as usize) }.to_vec();
+        let info = GuestInfo::from_vec(res);
+        unsafe { sys::virTypedParamsFree(params, nparams) };
+        Ok(info)
+    }
+
+    /// Starts a point in time backup job of the disks of the domain
+    ///
+    /// `backup_xml` describes the backup, see
+    /// <https://libvirt.org/formatbackup.html>. When `checkpoint_xml`
+    /// is given, a checkpoint is created at the same time so that a
+    /// later incremental backup can be taken from it.
+    ///
+    /// The job runs in the background: its progress is reported by
+    /// [`Domain::job_stats`], and once it ended its outcome is kept
+    /// and can be queried with `sys::VIR_DOMAIN_JOB_STATS_COMPLETED`.
+    /// The completed statistics are those of the last job that ended,
+    /// which is not the backup until the active job is gone.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn backup(dom: &virt::domain::Domain) -> Result<(), virt::error::Error> {
+    /// use virt::domain::BackupBeginFlags;
+    ///
+    /// dom.backup_begin("<domainbackup/>", None, BackupBeginFlags::empty())?;
+    /// while dom.job_stats(0)?.r#type != virt::sys::VIR_DOMAIN_JOB_NONE as i32 {
+    ///     std::thread::sleep(std::time::Duration::from_secs(1));
+    /// }
+    /// let stats = dom.job_stats(virt::sys::VIR_DOMAIN_JOB_STATS_COMPLETED)?;
+    /// assert_eq!(
+    ///     Some(virt::sys::VIR_DOMAIN_JOB_OPERATION_BACKUP as i32),
+    ///     stats.operation
+    /// );
+    /// assert_eq!(Some(true), stats.success);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBackupBegin>
+    pub fn backup_begin(
+        &self,
+        backup_xml: &str,
+        checkpoint_xml: Option<&str>,
+        flags: BackupBeginFlags,
+    ) -> Result<(), Error> {
+        let backup_xml_buf = CString::new(backup_xml)?;
+        let checkpoint_xml_buf = some_string_to_cstring!(checkpoint_xml);
+        let _ = check_neg!(unsafe {
+            sys::virDomainBackupBegin(
+                self.as_ptr(),
+                backup_xml_buf.as_ptr(),
+                some_cstring_to_c_chars!(checkpoint_xml_buf),
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the XML description of the running backup job
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBackupGetXMLDesc>
+    pub fn backup_xml_desc(&self, flags: u32) -> Result<String, Error> {
+        let xml = check_null!(unsafe {
+            sys::virDomainBackupGetXMLDesc(self.as_ptr(), flags as libc::c_uint)
+        })?;
+        Ok(unsafe { c_chars_to_string!(xml) })
+    }
+
     /// Get progress information about a background job running on this domain.
     /// NOTE: Only a subset of the fields in JobStats are populated by this method. If you want to
     /// populate more fields then you should use [`Self::job_stats`].
@@ -2123,60 +5925,237 @@ impl Domain {
         Ok(())
     }
 
-    /// Update the domain metadata XML
+    /// Update the domain metadata XML
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetMetadata>
+    pub fn set_metadata(
+        &self,
+        kind: i32,
+        metadata: Option<&str>,
+        key: Option<&str>,
+        uri: Option<&str>,
+        flags: u32,
+    ) -> Result<(), Error> {
+        let metadata_buf = some_string_to_cstring!(metadata);
+        let key_buf = some_string_to_cstring!(key);
+        let uri_buf = some_string_to_cstring!(uri);
+        let _ = check_neg!(unsafe {
+            sys::virDomainSetMetadata(
+                self.as_ptr(),
+                kind as libc::c_int,
+                some_cstring_to_c_chars!(metadata_buf),
+                some_cstring_to_c_chars!(key_buf),
+                some_cstring_to_c_chars!(uri_buf),
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the domain metadata XML
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetMetadata>
+    pub fn metadata(&self, kind: i32, uri: Option<&str>, flags: u32) -> Result<String, Error> {
+        let uri_buf = some_string_to_cstring!(uri);
+        let n = check_null!(unsafe {
+            sys::virDomainGetMetadata(
+                self.as_ptr(),
+                kind as libc::c_int,
+                some_cstring_to_c_chars!(uri_buf),
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(unsafe { c_chars_to_string!(n) })
+    }
+
+    /// Resize a block device on a running domain
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockResize>
+    pub fn block_resize(&self, disk: &str, size: u64, flags: u32) -> Result<(), Error> {
+        let disk_buf = CString::new(disk)?;
+        let _ = check_neg!(unsafe {
+            sys::virDomainBlockResize(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                size as libc::c_ulonglong,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Populates a disk image with data from its backing image chain.
+    ///
+    /// The job runs in the background, see [`Domain::block_job_info`].
+    /// `bandwidth` is in MiB/s, or in bytes/s with
+    /// [`BlockPullFlags::BANDWIDTH_BYTES`], 0 means unlimited.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockPull>
+    pub fn block_pull(
+        &self,
+        disk: &str,
+        bandwidth: u64,
+        flags: BlockPullFlags,
+    ) -> Result<(), Error> {
+        let disk_buf = CString::new(disk)?;
+        let _ = check_neg!(unsafe {
+            sys::virDomainBlockPull(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                bandwidth as libc::c_ulong,
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Populates a disk image with data from its backing image chain
+    /// down to `base`, or copies the disk to the destination `base`
+    /// with [`BlockRebaseFlags::COPY`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockRebase>
+    pub fn block_rebase(
+        &self,
+        disk: &str,
+        base: Option<&str>,
+        bandwidth: u64,
+        flags: BlockRebaseFlags,
+    ) -> Result<(), Error> {
+        let disk_buf = CString::new(disk)?;
+        let base_buf = some_string_to_cstring!(base);
+        let _ = check_neg!(unsafe {
+            sys::virDomainBlockRebase(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                some_cstring_to_c_chars!(base_buf),
+                bandwidth as libc::c_ulong,
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Commits the changes of the images between `top` and `base` of
+    /// the backing chain of a disk into `base`.
+    ///
+    /// `None` selects the active image for `top` and the deepest
+    /// backing image for `base`.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockCommit>
+    pub fn block_commit(
+        &self,
+        disk: &str,
+        base: Option<&str>,
+        top: Option<&str>,
+        bandwidth: u64,
+        flags: BlockCommitFlags,
+    ) -> Result<(), Error> {
+        let disk_buf = CString::new(disk)?;
+        let base_buf = some_string_to_cstring!(base);
+        let top_buf = some_string_to_cstring!(top);
+        let _ = check_neg!(unsafe {
+            sys::virDomainBlockCommit(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                some_cstring_to_c_chars!(base_buf),
+                some_cstring_to_c_chars!(top_buf),
+                bandwidth as libc::c_ulong,
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Copies a disk to the destination described by `dest_xml`, a
+    /// `<disk>` element, and mirrors the writes until the job is
+    /// aborted or pivoted.
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetMetadata>
-    pub fn set_metadata(
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockCopy>
+    pub fn block_copy(
         &self,
-        kind: i32,
-        metadata: Option<&str>,
-        key: Option<&str>,
-        uri: Option<&str>,
-        flags: u32,
+        disk: &str,
+        dest_xml: &str,
+        params: BlockCopyParameters,
+        flags: BlockCopyFlags,
     ) -> Result<(), Error> {
-        let metadata_buf = some_string_to_cstring!(metadata);
-        let key_buf = some_string_to_cstring!(key);
-        let uri_buf = some_string_to_cstring!(uri);
+        let disk_buf = CString::new(disk)?;
+        let dest_xml_buf = CString::new(dest_xml)?;
+        let mut cparams = params.to_vec();
         let _ = check_neg!(unsafe {
-            sys::virDomainSetMetadata(
+            sys::virDomainBlockCopy(
                 self.as_ptr(),
-                kind as libc::c_int,
-                some_cstring_to_c_chars!(metadata_buf),
-                some_cstring_to_c_chars!(key_buf),
-                some_cstring_to_c_chars!(uri_buf),
-                flags as libc::c_uint,
+                disk_buf.as_ptr(),
+                dest_xml_buf.as_ptr(),
+                cparams.as_mut_ptr(),
+                cparams.len() as libc::c_int,
+                flags.to_raw() as libc::c_uint,
             )
         })?;
         Ok(())
     }
 
-    /// Returns the domain metadata XML
+    /// Returns the progress of the block job running on a disk, or
+    /// `None` if there is no job.
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetMetadata>
-    pub fn metadata(&self, kind: i32, uri: Option<&str>, flags: u32) -> Result<String, Error> {
-        let uri_buf = some_string_to_cstring!(uri);
-        let n = check_null!(unsafe {
-            sys::virDomainGetMetadata(
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetBlockJobInfo>
+    pub fn block_job_info(
+        &self,
+        disk: &str,
+        flags: BlockJobInfoFlags,
+    ) -> Result<Option<BlockJobInfo>, Error> {
+        let mut pinfo = mem::MaybeUninit::uninit();
+        let disk_buf = CString::new(disk)?;
+        let ret = check_neg!(unsafe {
+            sys::virDomainGetBlockJobInfo(
                 self.as_ptr(),
-                kind as libc::c_int,
-                some_cstring_to_c_chars!(uri_buf),
-                flags as libc::c_uint,
+                disk_buf.as_ptr(),
+                pinfo.as_mut_ptr(),
+                flags.to_raw() as libc::c_uint,
             )
         })?;
-        Ok(unsafe { c_chars_to_string!(n) })
+        if ret == 0 {
+            return Ok(None);
+        }
+        Ok(Some(unsafe {
+            BlockJobInfo::from_ptr(&mut pinfo.assume_init())
+        }))
     }
 
-    /// Resize a block device on a running domain
+    /// Cancels the block job running on a disk.
     ///
-    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockResize>
-    pub fn block_resize(&self, disk: &str, size: u64, flags: u32) -> Result<(), Error> {
+    /// With [`BlockJobAbortFlags::PIVOT`], a copy or active
+    /// commit job that reached its ready state switches the disk to
+    /// the new image instead.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockJobAbort>
+    pub fn block_job_abort(&self, disk: &str, flags: BlockJobAbortFlags) -> Result<(), Error> {
         let disk_buf = CString::new(disk)?;
         let _ = check_neg!(unsafe {
-            sys::virDomainBlockResize(
+            sys::virDomainBlockJobAbort(
                 self.as_ptr(),
                 disk_buf.as_ptr(),
-                size as libc::c_ulonglong,
-                flags as libc::c_uint,
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Changes the bandwidth limit of the block job running on a disk.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainBlockJobSetSpeed>
+    pub fn block_job_set_speed(
+        &self,
+        disk: &str,
+        bandwidth: u64,
+        flags: BlockJobSetSpeedFlags,
+    ) -> Result<(), Error> {
+        let disk_buf = CString::new(disk)?;
+        let _ = check_neg!(unsafe {
+            sys::virDomainBlockJobSetSpeed(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                bandwidth as libc::c_ulong,
+                flags.to_raw() as libc::c_uint,
             )
         })?;
         Ok(())
@@ -2424,6 +6403,222 @@ impl Domain {
         Ok(())
     }
 
+    /// Returns the domain blkio parameters
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetBlkioParameters>
+    pub fn blkio_parameters(
+        &self,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<BlkioParameters, Error> {
+        let mut nparams: libc::c_int = 0;
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetBlkioParameters(
+                self.as_ptr(),
+                ptr::null_mut(),
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        let mut params: Vec<sys::virTypedParameter> = Vec::with_capacity(nparams as usize);
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetBlkioParameters(
+                self.as_ptr(),
+                params.as_mut_ptr(),
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        unsafe { params.set_len(nparams as usize) };
+        let blkio = BlkioParameters::from_vec(params.clone());
+        unsafe { sys::virTypedParamsClear(params.as_mut_ptr(), nparams) };
+
+        Ok(blkio)
+    }
+
+    /// Updates the domain blkio parameters
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetBlkioParameters>
+    pub fn set_blkio_parameters(
+        &self,
+        params: BlkioParameters,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
+        let mut cparams = params.to_vec();
+        let ret = check_neg!(unsafe {
+            sys::virDomainSetBlkioParameters(
+                self.as_ptr(),
+                cparams.as_mut_ptr(),
+                cparams.len() as libc::c_int,
+                flags as libc::c_uint,
+            )
+        });
+        unsafe { typed_params_release_c_chars!(cparams) };
+        ret?;
+        Ok(())
+    }
+
+    /// Returns the bandwidth parameters of a network interface
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetInterfaceParameters>
+    pub fn interface_parameters(
+        &self,
+        device: &str,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<InterfaceParameters, Error> {
+        let device_buf = CString::new(device)?;
+        let mut nparams: libc::c_int = 0;
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetInterfaceParameters(
+                self.as_ptr(),
+                device_buf.as_ptr(),
+                ptr::null_mut(),
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        let mut params: Vec<sys::virTypedParameter> = Vec::with_capacity(nparams as usize);
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetInterfaceParameters(
+                self.as_ptr(),
+                device_buf.as_ptr(),
+                params.as_mut_ptr(),
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        unsafe { params.set_len(nparams as usize) };
+        Ok(InterfaceParameters::from_vec(params))
+    }
+
+    /// Updates the bandwidth parameters of a network interface
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetInterfaceParameters>
+    pub fn set_interface_parameters(
+        &self,
+        device: &str,
+        params: InterfaceParameters,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
+        let device_buf = CString::new(device)?;
+        let mut cparams = params.to_vec();
+        let _ = check_neg!(unsafe {
+            sys::virDomainSetInterfaceParameters(
+                self.as_ptr(),
+                device_buf.as_ptr(),
+                cparams.as_mut_ptr(),
+                cparams.len() as libc::c_int,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the perf events enabled for the domain
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetPerfEvents>
+    pub fn perf_events(
+        &self,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<PerfEvents, Error> {
+        let mut nparams: libc::c_int = 0;
+        let mut params: sys::virTypedParameterPtr = ptr::null_mut();
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetPerfEvents(
+                self.as_ptr(),
+                &mut params,
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        let res = unsafe { slice::from_raw_parts(params, nparams as usize) }.to_vec();
+        unsafe { sys::virTypedParamsFree(params, nparams) };
+        Ok(PerfEvents::from_vec(res))
+    }
+
+    /// Enables or disables perf events of the domain
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetPerfEvents>
+    pub fn set_perf_events(
+        &self,
+        events: PerfEvents,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
+        let mut cparams = events.to_vec();
+        let _ = check_neg!(unsafe {
+            sys::virDomainSetPerfEvents(
+                self.as_ptr(),
+                cparams.as_mut_ptr(),
+                cparams.len() as libc::c_int,
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Returns the I/O throttling parameters of a disk
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainGetBlockIoTune>
+    pub fn block_io_tune(
+        &self,
+        disk: &str,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<BlockIoTuneParameters, Error> {
+        let disk_buf = CString::new(disk)?;
+        let mut nparams: libc::c_int = 0;
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetBlockIoTune(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                ptr::null_mut(),
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        let mut params: Vec<sys::virTypedParameter> = Vec::with_capacity(nparams as usize);
+        let _ = check_neg!(unsafe {
+            sys::virDomainGetBlockIoTune(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                params.as_mut_ptr(),
+                &mut nparams,
+                flags as libc::c_uint,
+            )
+        })?;
+        unsafe { params.set_len(nparams as usize) };
+        let tune = BlockIoTuneParameters::from_vec(params.clone());
+        unsafe { sys::virTypedParamsClear(params.as_mut_ptr(), nparams) };
+
+        Ok(tune)
+    }
+
+    /// Updates the I/O throttling parameters of a disk
+    ///
+    /// Only the parameters that are set are changed, a limit of 0
+    /// removes it.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain.html#virDomainSetBlockIoTune>
+    pub fn set_block_io_tune(
+        &self,
+        disk: &str,
+        params: BlockIoTuneParameters,
+        flags: sys::virDomainModificationImpact,
+    ) -> Result<(), Error> {
+        let disk_buf = CString::new(disk)?;
+        let mut cparams = params.to_vec();
+        let ret = check_neg!(unsafe {
+            sys::virDomainSetBlockIoTune(
+                self.as_ptr(),
+                disk_buf.as_ptr(),
+                cparams.as_mut_ptr(),
+                cparams.len() as libc::c_int,
+                flags as libc::c_uint,
+            )
+        });
+        unsafe { typed_params_release_c_chars!(cparams) };
+        ret?;
+        Ok(())
+    }
+
     /// Returns a list of domain snapshot objects
     ///
     /// See <https://libvirt.org/html/libvirt-libvirt-domain-snapshot.html#virDomainListAllSnapshots>
@@ -2670,6 +6865,69 @@ impl Domain {
         Ok(unsafe { DomainSnapshot::from_ptr(ptr) })
     }
 
+    /// Create a new domain checkpoint
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainCheckpointCreateXML>
+    pub fn checkpoint_create_xml(
+        &self,
+        xml: &str,
+        flags: DomainCheckpointCreateFlags,
+    ) -> Result<DomainCheckpoint, Error> {
+        let xml_buf = CString::new(xml)?;
+        let ptr = check_null!(unsafe {
+            sys::virDomainCheckpointCreateXML(
+                self.as_ptr(),
+                xml_buf.as_ptr(),
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+        Ok(unsafe { DomainCheckpoint::from_ptr(ptr) })
+    }
+
+    /// Get a handle to a named checkpoint.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainCheckpointLookupByName>
+    pub fn checkpoint_lookup_by_name(
+        &self,
+        name: &str,
+        flags: u32,
+    ) -> Result<DomainCheckpoint, Error> {
+        let name_buf = CString::new(name)?;
+        let ptr = check_null!(unsafe {
+            sys::virDomainCheckpointLookupByName(
+                self.as_ptr(),
+                name_buf.as_ptr(),
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(unsafe { DomainCheckpoint::from_ptr(ptr) })
+    }
+
+    /// Returns a list of domain checkpoint objects
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainListAllCheckpoints>
+    pub fn list_all_checkpoints(
+        &self,
+        flags: DomainCheckpointListFlags,
+    ) -> Result<Vec<DomainCheckpoint>, Error> {
+        let mut checkpoints: *mut sys::virDomainCheckpointPtr = ptr::null_mut();
+        let size = check_neg!(unsafe {
+            sys::virDomainListAllCheckpoints(
+                self.as_ptr(),
+                &mut checkpoints,
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+
+        let mut array: Vec<DomainCheckpoint> = Vec::new();
+        for x in 0..size as isize {
+            array.push(unsafe { DomainCheckpoint::from_ptr(*checkpoints.offset(x)) });
+        }
+        unsafe { libc::free(checkpoints as *mut libc::c_void) };
+
+        Ok(array)
+    }
+
     /// Get a handle to the current snapshot
     ///
     /// See <https://libvirt.org/html/libvirt-libvirt-domain-snapshot.html#virDomainSnapshotCurrent>
@@ -2689,3 +6947,118 @@ impl Domain {
         Ok(ret as u32)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typedparams::{FieldOut, ParamOut};
+
+    fn param(name: &str, value: ParamOut) -> sys::virTypedParameter {
+        let field = FieldOut {
+            name: name.to_string(),
+            value,
+        };
+        to_params(vec![field]).remove(0)
+    }
+
+    fn string(name: &str, value: &str) -> sys::virTypedParameter {
+        param(name, ParamOut::String(&Some(value.to_string())))
+    }
+
+    #[test]
+    fn test_guest_info_from_vec() {
+        let params = vec![
+            string("hostname", "guest"),
+            param("user.count", ParamOut::UInt32(&Some(1))),
+            string("user.0.name", "root"),
+            param("user.0.login-time", ParamOut::UInt64(&Some(1700000000000))),
+            string("os.id", "fedora"),
+            string("timezone.name", "CET"),
+            param("timezone.offset", ParamOut::Int32(&Some(3600))),
+            param("fs.count", ParamOut::UInt32(&Some(2))),
+            string("fs.1.mountpoint", "/boot"),
+            string("fs.0.mountpoint", "/"),
+            string("fs.0.fstype", "xfs"),
+            param("fs.0.disk.count", ParamOut::UInt32(&Some(2))),
+            string("fs.0.disk.1.alias", "vdb"),
+            string("fs.0.disk.0.alias", "vda"),
+            string("fs.0.disk.0.device", "/dev/vda1"),
+            string("disk.0.name", "/dev/md0"),
+            param("disk.0.partition", ParamOut::Bool(&Some(false))),
+            param("disk.0.dependency.count", ParamOut::UInt32(&Some(2))),
+            string("disk.0.dependency.0.name", "/dev/vda"),
+            string("disk.0.dependency.1.name", "/dev/vdb"),
+            string("if.0.name", "eth0"),
+            string("if.0.hwaddr", "52:54:00:12:34:56"),
+            param("if.0.addr.count", ParamOut::UInt32(&Some(2))),
+            string("if.0.addr.0.type", "ipv4"),
+            string("if.0.addr.0.addr", "192.168.122.2"),
+            param("if.0.addr.0.prefix", ParamOut::UInt32(&Some(24))),
+            string("if.0.addr.1.type", "ipv6"),
+            string("if.0.addr.1.addr", "fe80::1"),
+            param("load.1m", ParamOut::Float64(&Some(0.5))),
+            string("unknown.0.key", "ignored"),
+        ];
+        let info = GuestInfo::from_vec(params.clone());
+        unsafe { typed_params_release_c_chars!(params) };
+
+        assert_eq!(Some("guest"), info.hostname.as_deref());
+        assert_eq!(1, info.users.len());
+        assert_eq!(Some("root"), info.users[0].name.as_deref());
+        assert_eq!(Some(1700000000000), info.users[0].login_time);
+        assert_eq!(Some("fedora"), info.os.and_then(|os| os.id).as_deref());
+        let timezone = info.timezone.unwrap();
+        assert_eq!(Some("CET"), timezone.name.as_deref());
+        assert_eq!(Some(3600), timezone.offset);
+
+        assert_eq!(2, info.filesystems.len());
+        let fs = &info.filesystems[0];
+        assert_eq!(Some("/"), fs.mountpoint.as_deref());
+        assert_eq!(Some("xfs"), fs.fstype.as_deref());
+        assert_eq!(2, fs.disks.len());
+        assert_eq!(Some("vda"), fs.disks[0].alias.as_deref());
+        assert_eq!(Some("/dev/vda1"), fs.disks[0].device.as_deref());
+        assert_eq!(Some("vdb"), fs.disks[1].alias.as_deref());
+        assert_eq!(Some("/boot"), info.filesystems[1].mountpoint.as_deref());
+        assert!(info.filesystems[1].disks.is_empty());
+
+        assert_eq!(1, info.disks.len());
+        assert_eq!(Some("/dev/md0"), info.disks[0].name.as_deref());
+        assert_eq!(Some(false), info.disks[0].partition);
+        assert_eq!(vec!["/dev/vda", "/dev/vdb"], info.disks[0].dependencies);
+
+        assert_eq!(1, info.interfaces.len());
+        let iface = &info.interfaces[0];
+        assert_eq!(Some("eth0"), iface.name.as_deref());
+        assert_eq!(Some("52:54:00:12:34:56"), iface.hwaddr.as_deref());
+        assert_eq!(2, iface.addrs.len());
+        assert_eq!(Some("ipv4"), iface.addrs[0].r#type.as_deref());
+        assert_eq!(Some("192.168.122.2"), iface.addrs[0].addr.as_deref());
+        assert_eq!(Some(24), iface.addrs[0].prefix);
+        assert_eq!(Some("fe80::1"), iface.addrs[1].addr.as_deref());
+        assert_eq!(None, iface.addrs[1].prefix);
+
+        assert_eq!(Some(0.5), info.load.and_then(|load| load.load_1m));
+    }
+
+    #[test]
+    fn test_guest_vcpus_from_vec() {
+        let params = vec![
+            string("vcpus", "0-3"),
+            string("online", "0-1,3"),
+            string("offlinable", "1-3"),
+            string("unknown", "5"),
+        ];
+        let vcpus = GuestVcpus::from_vec(params.clone());
+        unsafe { typed_params_release_c_chars!(params) };
+        let vcpus = vcpus.unwrap();
+        assert_eq!(vec![0, 1, 2, 3], vcpus.vcpus.iter().collect::<Vec<u32>>());
+        assert_eq!(vec![0, 1, 3], vcpus.online.iter().collect::<Vec<u32>>());
+        assert_eq!(vec![1, 2, 3], vcpus.offlinable.iter().collect::<Vec<u32>>());
+
+        let params = vec![string("vcpus", "0-x")];
+        let vcpus = GuestVcpus::from_vec(params.clone());
+        unsafe { typed_params_release_c_chars!(params) };
+        assert!(vcpus.is_err());
+    }
+}
@@ -0,0 +1,213 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <https://www.gnu.org/licenses/>.
+ */
+
+use std::ptr;
+
+use crate::connect::Connect;
+use crate::domain::Domain;
+use crate::enumutil::impl_flags;
+use crate::error::Error;
+use crate::util::{check_neg, check_null};
+
+impl_flags! {
+    /// Flags for [`Domain::checkpoint_create_xml`].
+    struct DomainCheckpointCreateFlags: sys::virDomainCheckpointCreateFlags {
+        /// Restore the metadata of an existing checkpoint.
+        const REDEFINE = sys::VIR_DOMAIN_CHECKPOINT_CREATE_REDEFINE;
+        /// Quiesce the guest filesystems through the guest agent.
+        const QUIESCE = sys::VIR_DOMAIN_CHECKPOINT_CREATE_QUIESCE;
+        /// Check that the redefined checkpoint is consistent with the
+        /// disk bitmaps.
+        const REDEFINE_VALIDATE = sys::VIR_DOMAIN_CHECKPOINT_CREATE_REDEFINE_VALIDATE;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`DomainCheckpoint::xml_desc`].
+    struct DomainCheckpointXMLFlags: sys::virDomainCheckpointXMLFlags {
+        /// Include security sensitive information.
+        const SECURE = sys::VIR_DOMAIN_CHECKPOINT_XML_SECURE;
+        /// Omit the `<domain>` element.
+        const NO_DOMAIN = sys::VIR_DOMAIN_CHECKPOINT_XML_NO_DOMAIN;
+        /// Include the size of the changes since the checkpoint.
+        const SIZE = sys::VIR_DOMAIN_CHECKPOINT_XML_SIZE;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`Domain::list_all_checkpoints`] and
+    /// [`DomainCheckpoint::list_all_children`].
+    struct DomainCheckpointListFlags: sys::virDomainCheckpointListFlags {
+        /// Only list checkpoints without a parent, when listing the
+        /// checkpoints of a domain.
+        const ROOTS = sys::VIR_DOMAIN_CHECKPOINT_LIST_ROOTS;
+        /// List all descendants instead of the direct children, when
+        /// listing the children of a checkpoint.
+        const DESCENDANTS = sys::VIR_DOMAIN_CHECKPOINT_LIST_DESCENDANTS;
+        /// Sort parents before their children.
+        const TOPOLOGICAL = sys::VIR_DOMAIN_CHECKPOINT_LIST_TOPOLOGICAL;
+        /// Only list checkpoints without children.
+        const LEAVES = sys::VIR_DOMAIN_CHECKPOINT_LIST_LEAVES;
+        /// Only list checkpoints with children.
+        const NO_LEAVES = sys::VIR_DOMAIN_CHECKPOINT_LIST_NO_LEAVES;
+    }
+}
+
+impl_flags! {
+    /// Flags for [`DomainCheckpoint::delete`].
+    struct DomainCheckpointDeleteFlags: sys::virDomainCheckpointDeleteFlags {
+        /// Delete the descendants of the checkpoint too.
+        const CHILDREN = sys::VIR_DOMAIN_CHECKPOINT_DELETE_CHILDREN;
+        /// Only delete the libvirt metadata, not the disk bitmaps.
+        const METADATA_ONLY = sys::VIR_DOMAIN_CHECKPOINT_DELETE_METADATA_ONLY;
+        /// Only delete the descendants of the checkpoint.
+        const CHILDREN_ONLY = sys::VIR_DOMAIN_CHECKPOINT_DELETE_CHILDREN_ONLY;
+    }
+}
+
+/// Provides APIs for the management of domain checkpoints.
+///
+/// See <https://libvirt.org/formatcheckpoint.html>
+#[derive(Debug)]
+pub struct DomainCheckpoint {
+    ptr: sys::virDomainCheckpointPtr,
+}
+
+unsafe impl Send for DomainCheckpoint {}
+unsafe impl Sync for DomainCheckpoint {}
+
+impl Drop for DomainCheckpoint {
+    fn drop(&mut self) {
+        if let Err(e) = check_neg!(unsafe { sys::virDomainCheckpointFree(self.as_ptr()) }) {
+            panic!("Unable to drop reference on domain checkpoint: {e}")
+        }
+    }
+}
+
+impl Clone for DomainCheckpoint {
+    /// Creates a copy of a domain checkpoint.
+    ///
+    /// Increments the internal reference counter on the given
+    /// checkpoint.
+    fn clone(&self) -> Self {
+        if let Err(e) = check_neg!(unsafe { sys::virDomainCheckpointRef(self.as_ptr()) }) {
+            panic!("Unable to add reference on domain checkpoint: {e}")
+        }
+        unsafe { DomainCheckpoint::from_ptr(self.as_ptr()) }
+    }
+}
+
+impl DomainCheckpoint {
+    /// # Safety
+    ///
+    /// The caller must ensure that the pointer is valid.
+    /// The rust wrapper will own the reference count
+    /// for the C object upon return.
+    pub unsafe fn from_ptr(ptr: sys::virDomainCheckpointPtr) -> DomainCheckpoint {
+        DomainCheckpoint { ptr }
+    }
+
+    /// # Safety
+    ///
+    /// The pointer returned by this method is a copy of
+    /// a pointer that is normally tracked by reference
+    /// counting in the underlying implementation. Creating
+    /// a copy of the pointer explicitly circumvents that
+    /// reference counting. The returned pointer may be
+    /// invalidated if this object is dropped.
+    pub unsafe fn as_ptr(&self) -> sys::virDomainCheckpointPtr {
+        self.ptr
+    }
+
+    pub fn connect(&self) -> Result<Connect, Error> {
+        let ptr = check_null!(unsafe { sys::virDomainCheckpointGetConnect(self.as_ptr()) })?;
+        if let Err(e) = check_neg!(unsafe { sys::virConnectRef(ptr) }) {
+            panic!("Unable to add reference on connection: {e}")
+        }
+        Ok(unsafe { Connect::from_ptr(ptr) })
+    }
+
+    pub fn domain(&self) -> Result<Domain, Error> {
+        let ptr = check_null!(unsafe { sys::virDomainCheckpointGetDomain(self.as_ptr()) })?;
+        if let Err(e) = check_neg!(unsafe { sys::virDomainRef(ptr) }) {
+            panic!("Unable to add reference on domain: {e}")
+        }
+        Ok(unsafe { Domain::from_ptr(ptr) })
+    }
+
+    /// Returns the checkpoint name
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainCheckpointGetName>
+    pub fn name(&self) -> Result<String, Error> {
+        let n = check_null!(unsafe { sys::virDomainCheckpointGetName(self.as_ptr()) })?;
+        Ok(unsafe { c_chars_to_string!(n, nofree) })
+    }
+
+    /// Returns the checkpoint XML configuration
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainCheckpointGetXMLDesc>
+    pub fn xml_desc(&self, flags: DomainCheckpointXMLFlags) -> Result<String, Error> {
+        let xml = check_null!(unsafe {
+            sys::virDomainCheckpointGetXMLDesc(self.as_ptr(), flags.to_raw() as libc::c_uint)
+        })?;
+        Ok(unsafe { c_chars_to_string!(xml) })
+    }
+
+    /// Get a handle to the parent checkpoint, if one exists.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainCheckpointGetParent>
+    pub fn parent(&self, flags: u32) -> Result<DomainCheckpoint, Error> {
+        let ptr = check_null!(unsafe {
+            sys::virDomainCheckpointGetParent(self.as_ptr(), flags as libc::c_uint)
+        })?;
+        Ok(unsafe { DomainCheckpoint::from_ptr(ptr) })
+    }
+
+    /// Delete a checkpoint.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainCheckpointDelete>
+    pub fn delete(&self, flags: DomainCheckpointDeleteFlags) -> Result<(), Error> {
+        let _ = check_neg!(unsafe {
+            sys::virDomainCheckpointDelete(self.as_ptr(), flags.to_raw() as libc::c_uint)
+        })?;
+        Ok(())
+    }
+
+    /// Get all checkpoint object children for this checkpoint.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-domain-checkpoint.html#virDomainCheckpointListAllChildren>
+    pub fn list_all_children(
+        &self,
+        flags: DomainCheckpointListFlags,
+    ) -> Result<Vec<DomainCheckpoint>, Error> {
+        let mut checkpoints: *mut sys::virDomainCheckpointPtr = ptr::null_mut();
+        let size = check_neg!(unsafe {
+            sys::virDomainCheckpointListAllChildren(
+                self.as_ptr(),
+                &mut checkpoints,
+                flags.to_raw() as libc::c_uint,
+            )
+        })?;
+
+        let mut array: Vec<DomainCheckpoint> = Vec::new();
+        for x in 0..size as isize {
+            array.push(unsafe { DomainCheckpoint::from_ptr(*checkpoints.offset(x)) });
+        }
+        unsafe { libc::free(checkpoints as *mut libc::c_void) };
+
+        Ok(array)
+    }
+}
@@ -207,10 +207,69 @@ macro_rules! impl_enum_to {
     };
 }
 
+/// Declares a set of flags backed by a libvirt C flags type, with
+/// one associated constant per flag.
+macro_rules! impl_flags {
+    (
+        $(#[$attr:meta])*
+        struct $type:ident: $raw:ty {
+            $($(#[$flag_attr:meta])* const $flag:ident = $value:path;)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+        pub struct $type($raw);
+
+        impl $type {
+            $($(#[$flag_attr])* pub const $flag: $type = $type($value);)*
+
+            /// Returns a set with no flag.
+            pub const fn empty() -> Self {
+                $type(0)
+            }
+
+            /// Converts libvirt C flags to Rust flags, keeping the
+            /// flags unknown to this crate.
+            pub const fn from_raw(raw: $raw) -> Self {
+                $type(raw)
+            }
+
+            /// Converts Rust flags to libvirt C flags.
+            pub const fn to_raw(self) -> $raw {
+                self.0
+            }
+
+            pub const fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Returns whether all the flags of `other` are set.
+            pub const fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+        }
+
+        impl std::ops::BitOr for $type {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                $type(self.0 | other.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for $type {
+            fn bitor_assign(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+        }
+    };
+}
+
 pub(crate) use impl_enum;
 pub(crate) use impl_enum_display;
 pub(crate) use impl_enum_from;
 pub(crate) use impl_enum_to;
+pub(crate) use impl_flags;
 
 #[cfg(test)]
 mod tests {
@@ -220,6 +279,9 @@ mod tests {
     const BAR: u32 = 1;
     const BAZ: u32 = 2;
 
+    const FOO_FLAG: u32 = 1;
+    const BAR_FLAG: u32 = 2;
+
     #[derive(Debug, PartialEq, Clone, Copy)]
     enum Example {
         Foo,
@@ -229,6 +291,13 @@ mod tests {
 
     type ExampleEnum = Enum<Example, u32>;
 
+    impl_flags! {
+        struct ExampleFlags: u32 {
+            const FOO = FOO_FLAG;
+            const BAR = BAR_FLAG;
+        }
+    }
+
     impl_enum! {
         enum: Example,
         raw: u32,
@@ -345,4 +414,24 @@ mod tests {
 
         assert_eq!(r.unwrap_or(Example::Foo), Example::Foo);
     }
+
+    #[test]
+    fn test_flags() {
+        let mut f = ExampleFlags::empty();
+        assert!(f.is_empty());
+        assert_eq!(f, ExampleFlags::default());
+
+        f |= ExampleFlags::FOO;
+        assert!(f.contains(ExampleFlags::FOO));
+        assert!(!f.contains(ExampleFlags::BAR));
+        assert!(!f.contains(ExampleFlags::FOO | ExampleFlags::BAR));
+
+        let f = f | ExampleFlags::BAR;
+        assert!(f.contains(ExampleFlags::FOO | ExampleFlags::BAR));
+        assert_eq!(f.to_raw(), FOO_FLAG | BAR_FLAG);
+
+        let f = ExampleFlags::from_raw(8 | BAR_FLAG);
+        assert!(f.contains(ExampleFlags::BAR));
+        assert_eq!(f.to_raw(), 8 | BAR_FLAG);
+    }
 }
@@ -19,6 +19,7 @@
 use std::error::Error as StdError;
 use std::ffi::CStr;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
 
 use crate::enumutil::{impl_enum, Enum, RawEnum};
 
@@ -670,6 +671,17 @@ impl Error {
         }
     }
 
+    /// Creates an error raised by the bindings themselves rather than
+    /// by libvirt.
+    pub(crate) fn new(code: ErrorNumber, message: String) -> Error {
+        Error {
+            code: ErrorNumberEnum::from(code),
+            domain: ErrorDomainEnum::from(ErrorDomain::None),
+            message,
+            level: ErrorLevel::Error,
+        }
+    }
+
     unsafe fn from_raw(ptr: sys::virErrorPtr) -> Error {
         let code = ErrorNumberEnum::from_raw((*ptr).code as sys::virErrorNumber);
         let domain = ErrorDomainEnum::from_raw((*ptr).domain as sys::virErrorDomain);
@@ -730,6 +742,36 @@ impl From<std::ffi::NulError> for Error {
     }
 }
 
+impl From<Error> for io::Error {
+    /// Wraps a libvirt error, picking the closest [`io::ErrorKind`]
+    /// for its code.
+    fn from(err: Error) -> Self {
+        let kind = match err.code().known() {
+            Some(ErrorNumber::NoMemory) => io::ErrorKind::OutOfMemory,
+            Some(ErrorNumber::InvalidArg) => io::ErrorKind::InvalidInput,
+            Some(
+                ErrorNumber::NoSupport
+                | ErrorNumber::ArgumentUnsupported
+                | ErrorNumber::ConfigUnsupported
+                | ErrorNumber::OperationUnsupported,
+            ) => io::ErrorKind::Unsupported,
+            Some(ErrorNumber::OperationTimeout | ErrorNumber::AgentCommandTimeout) => {
+                io::ErrorKind::TimedOut
+            }
+            Some(
+                ErrorNumber::AuthFailed
+                | ErrorNumber::AuthCancelled
+                | ErrorNumber::OperationDenied
+                | ErrorNumber::AccessDenied,
+            ) => io::ErrorKind::PermissionDenied,
+            Some(ErrorNumber::NoConnect | ErrorNumber::InvalidConn) => io::ErrorKind::NotConnected,
+            Some(ErrorNumber::OperationAborted) => io::ErrorKind::ConnectionAborted,
+            _ => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, err)
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match self.level() {
@@ -17,8 +17,14 @@
  */
 
 use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use std::{io, mem, ptr};
 
-use crate::error::Error;
+use crate::callback::abort_on_panic;
+use crate::error::{Error, ErrorNumber};
 use crate::util::check_neg;
 
 // wrapper for callbacks
@@ -31,7 +37,7 @@ unsafe extern "C" fn event_callback<
     opaque: *mut libc::c_void,
 ) {
     let ecd = &mut *(opaque as *mut EventCallbackData<F>);
-    (ecd.cb)(watch, fd, events as sys::virEventHandleType, ecd.opaque);
+    abort_on_panic(|| (ecd.cb)(watch, fd, events as sys::virEventHandleType, ecd.opaque));
 }
 
 unsafe extern "C" fn event_free<
@@ -39,7 +45,7 @@ unsafe extern "C" fn event_free<
 >(
     opaque: *mut libc::c_void,
 ) {
-    let _ = Box::from_raw(opaque as *mut EventCallbackData<F>);
+    abort_on_panic(|| drop(Box::from_raw(opaque as *mut EventCallbackData<F>)));
 }
 
 struct EventCallbackData<
@@ -85,21 +91,21 @@ pub fn event_add_handle<
     opaque: *mut libc::c_void,
 ) -> Result<EventHandleWatch, Error> {
     let event_callback_data: Box<EventCallbackData<F>> = Box::new(EventCallbackData { cb, opaque });
+    let data = Box::into_raw(event_callback_data) as *mut libc::c_void;
 
     let ret = unsafe {
         sys::virEventAddHandle(
             fd,
             events as libc::c_int,
             Some(event_callback::<F>),
-            Box::into_raw(event_callback_data) as *mut libc::c_void,
+            data,
             Some(event_free::<F>),
         )
     };
     if ret == -1 {
-        unsafe {
-            let _ = Box::from_raw(opaque as *mut EventCallbackData<F>);
-        }
-        return Err(Error::last_error());
+        let err = Error::last_error();
+        unsafe { event_free::<F>(data) };
+        return Err(err);
     }
     Ok(EventHandleWatch(ret))
 }
@@ -110,13 +116,13 @@ unsafe extern "C" fn event_timeout_callback<F: FnMut(libc::c_int, *mut libc::c_v
     opaque: *mut libc::c_void,
 ) {
     let ecd = &mut *(opaque as *mut EventTimeoutCallbackData<F>);
-    (ecd.cb)(timer, ecd.opaque);
+    abort_on_panic(|| (ecd.cb)(timer, ecd.opaque));
 }
 
 unsafe extern "C" fn event_timeout_free<F: FnMut(libc::c_int, *mut libc::c_void)>(
     opaque: *mut libc::c_void,
 ) {
-    let _ = Box::from_raw(opaque as *mut EventTimeoutCallbackData<F>);
+    abort_on_panic(|| drop(Box::from_raw(opaque as *mut EventTimeoutCallbackData<F>)));
 }
 
 struct EventTimeoutCallbackData<F: FnMut(libc::c_int, *mut libc::c_void)> {
@@ -157,20 +163,20 @@ pub fn event_add_timeout<F: 'static + FnMut(libc::c_int, *mut libc::c_void)>(
 ) -> Result<EventTimeoutWatch, Error> {
     let event_timeout_callback_data: Box<EventTimeoutCallbackData<F>> =
         Box::new(EventTimeoutCallbackData { cb, opaque });
+    let data = Box::into_raw(event_timeout_callback_data) as *mut libc::c_void;
 
     let ret = unsafe {
         sys::virEventAddTimeout(
             timeout,
             Some(event_timeout_callback::<F>),
-            Box::into_raw(event_timeout_callback_data) as *mut libc::c_void,
+            data,
             Some(event_timeout_free::<F>),
         )
     };
     if ret == -1 {
-        unsafe {
-            let _ = Box::from_raw(opaque as *mut EventTimeoutCallbackData<F>);
-        }
-        return Err(Error::last_error());
+        let err = Error::last_error();
+        unsafe { event_timeout_free::<F>(data) };
+        return Err(err);
     }
     Ok(EventTimeoutWatch(ret))
 }
@@ -184,3 +190,659 @@ pub fn event_run_default_impl() -> Result<(), Error> {
     let _ = check_neg!(unsafe { sys::virEventRunDefaultImpl() })?;
     Ok(())
 }
+
+/// Default libvirt event loop running in a background thread.
+///
+/// The loop is stopped and the thread joined by [`Self::stop`] or
+/// when the value is dropped.
+///
+/// # Examples
+///
+/// ````no_run
+/// use virt::connect::Connect;
+/// use virt::event::EventLoopThread;
+///
+/// let event_loop = EventLoopThread::start().unwrap();
+/// let conn = Connect::open(Some("test:///default")).unwrap();
+/// // register event callbacks...
+/// drop(conn);
+/// event_loop.stop().unwrap();
+/// ````
+pub struct EventLoopThread {
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EventLoopThread {
+    /// Registers the default event loop implementation and starts
+    /// running it in a new thread.
+    pub fn start() -> Result<EventLoopThread, Error> {
+        event_register_default_impl()?;
+        let running = Arc::new(AtomicBool::new(true));
+        let keep_running = Arc::clone(&running);
+        let thread = thread::Builder::new()
+            .name("libvirt-event-loop".into())
+            .spawn(move || {
+                while keep_running.load(Ordering::SeqCst) {
+                    if event_run_default_impl().is_err() {
+                        break;
+                    }
+                }
+            })
+            .map_err(|e| Error::new(ErrorNumber::SystemError, e.to_string()))?;
+        Ok(EventLoopThread {
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Stops the event loop and waits for the thread to exit.
+    pub fn stop(mut self) -> Result<(), Error> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Result<(), Error> {
+        if self.thread.is_none() {
+            return Ok(());
+        }
+        self.running.store(false, Ordering::SeqCst);
+        // The loop may be blocked waiting for events, a timer firing
+        // right away makes it go through one more iteration and
+        // notice it has to stop. The thread is only taken once the
+        // timer exists so that a failed stop can be retried on drop.
+        let wakeup = event_add_timeout(0, |_, _| {}, ptr::null_mut())?;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        wakeup.event_remove_timeout()
+    }
+}
+
+impl Drop for EventLoopThread {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// Handle callback handed over by libvirt to an [`EventLoop`].
+///
+/// Dropping it releases the opaque data libvirt associated with the
+/// callback, so it must not be dropped from within
+/// [`EventLoop::remove_handle`], only once the loop is back to a
+/// clean state (e.g. at the end of the current iteration).
+pub struct EventHandleCallback {
+    cb: sys::virEventHandleCallback,
+    opaque: *mut libc::c_void,
+    ff: sys::virFreeCallback,
+}
+
+// libvirt callbacks do their own locking and may be invoked from
+// whichever thread runs the event loop.
+unsafe impl Send for EventHandleCallback {}
+unsafe impl Sync for EventHandleCallback {}
+
+impl EventHandleCallback {
+    /// Notifies libvirt that `events` occurred on the file descriptor
+    /// of `watch`.
+    pub fn call(&self, watch: libc::c_int, fd: RawFd, events: sys::virEventHandleType) {
+        if let Some(cb) = self.cb {
+            unsafe { cb(watch, fd, events as libc::c_int, self.opaque) };
+        }
+    }
+}
+
+impl Drop for EventHandleCallback {
+    fn drop(&mut self) {
+        if let Some(ff) = self.ff {
+            unsafe { ff(self.opaque) };
+        }
+    }
+}
+
+/// Timeout callback handed over by libvirt to an [`EventLoop`].
+///
+/// The same rules as for [`EventHandleCallback`] apply when dropping
+/// it.
+pub struct EventTimeoutCallback {
+    cb: sys::virEventTimeoutCallback,
+    opaque: *mut libc::c_void,
+    ff: sys::virFreeCallback,
+}
+
+unsafe impl Send for EventTimeoutCallback {}
+unsafe impl Sync for EventTimeoutCallback {}
+
+impl EventTimeoutCallback {
+    /// Notifies libvirt that the timer `timer` expired.
+    pub fn call(&self, timer: libc::c_int) {
+        if let Some(cb) = self.cb {
+            unsafe { cb(timer, self.opaque) };
+        }
+    }
+}
+
+impl Drop for EventTimeoutCallback {
+    fn drop(&mut self) {
+        if let Some(ff) = self.ff {
+            unsafe { ff(self.opaque) };
+        }
+    }
+}
+
+/// Event loop implementation used by libvirt to watch file
+/// descriptors and run timers, installed with [`register_event_impl`].
+///
+/// Callbacks are free to call back into the event loop, so
+/// implementations must not hold internal locks while invoking them.
+pub trait EventLoop: Send + Sync {
+    /// Starts watching `fd` for `events`, a mask of
+    /// `sys::VIR_EVENT_HANDLE_*` flags. Returns a positive watch
+    /// number, or gives `cb` back on failure: the caller of libvirt
+    /// then releases the data itself, so it must not be dropped.
+    fn add_handle(
+        &self,
+        fd: RawFd,
+        events: sys::virEventHandleType,
+        cb: EventHandleCallback,
+    ) -> Result<libc::c_int, EventHandleCallback>;
+
+    /// Changes the set of events monitored for `watch`.
+    fn update_handle(&self, watch: libc::c_int, events: sys::virEventHandleType);
+
+    /// Stops watching `watch`. Returns `false` if it is unknown.
+    fn remove_handle(&self, watch: libc::c_int) -> bool;
+
+    /// Registers a timer firing every `timeout` milliseconds, on every
+    /// loop iteration when 0, or never when -1. Returns a positive
+    /// timer number, or gives `cb` back on failure like
+    /// [`Self::add_handle`].
+    fn add_timeout(
+        &self,
+        timeout: libc::c_int,
+        cb: EventTimeoutCallback,
+    ) -> Result<libc::c_int, EventTimeoutCallback>;
+
+    /// Changes the frequency of `timer`.
+    fn update_timeout(&self, timer: libc::c_int, timeout: libc::c_int);
+
+    /// Deletes `timer`. Returns `false` if it is unknown.
+    fn remove_timeout(&self, timer: libc::c_int) -> bool;
+}
+
+static EVENT_IMPL: Mutex<Option<Arc<dyn EventLoop>>> = Mutex::new(None);
+
+fn event_impl() -> Option<Arc<dyn EventLoop>> {
+    match EVENT_IMPL.lock() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+unsafe extern "C" fn event_impl_add_handle(
+    fd: libc::c_int,
+    events: libc::c_int,
+    cb: sys::virEventHandleCallback,
+    opaque: *mut libc::c_void,
+    ff: sys::virFreeCallback,
+) -> libc::c_int {
+    abort_on_panic(|| {
+        let el = match event_impl() {
+            Some(el) => el,
+            None => return -1,
+        };
+        let cb = EventHandleCallback { cb, opaque, ff };
+        match el.add_handle(fd, events as sys::virEventHandleType, cb) {
+            Ok(watch) => watch,
+            Err(cb) => {
+                // libvirt does not call ff when adding fails.
+                mem::forget(cb);
+                -1
+            }
+        }
+    })
+}
+
+unsafe extern "C" fn event_impl_update_handle(watch: libc::c_int, events: libc::c_int) {
+    abort_on_panic(|| {
+        if let Some(el) = event_impl() {
+            el.update_handle(watch, events as sys::virEventHandleType);
+        }
+    })
+}
+
+unsafe extern "C" fn event_impl_remove_handle(watch: libc::c_int) -> libc::c_int {
+    abort_on_panic(|| match event_impl() {
+        Some(el) if el.remove_handle(watch) => 0,
+        _ => -1,
+    })
+}
+
+unsafe extern "C" fn event_impl_add_timeout(
+    timeout: libc::c_int,
+    cb: sys::virEventTimeoutCallback,
+    opaque: *mut libc::c_void,
+    ff: sys::virFreeCallback,
+) -> libc::c_int {
+    abort_on_panic(|| {
+        let el = match event_impl() {
+            Some(el) => el,
+            None => return -1,
+        };
+        let cb = EventTimeoutCallback { cb, opaque, ff };
+        match el.add_timeout(timeout, cb) {
+            Ok(timer) => timer,
+            Err(cb) => {
+                // libvirt does not call ff when adding fails.
+                mem::forget(cb);
+                -1
+            }
+        }
+    })
+}
+
+unsafe extern "C" fn event_impl_update_timeout(timer: libc::c_int, timeout: libc::c_int) {
+    abort_on_panic(|| {
+        if let Some(el) = event_impl() {
+            el.update_timeout(timer, timeout);
+        }
+    })
+}
+
+unsafe extern "C" fn event_impl_remove_timeout(timer: libc::c_int) -> libc::c_int {
+    abort_on_panic(|| match event_impl() {
+        Some(el) if el.remove_timeout(timer) => 0,
+        _ => -1,
+    })
+}
+
+/// Installs `event_loop` as the libvirt event loop implementation.
+///
+/// This replaces [`event_register_default_impl`] and must be called
+/// before opening any connection. The application is then in charge
+/// of running the loop.
+///
+/// Handles and timers already added to the loop could not be moved to
+/// another one, so this can only be called once per process and fails
+/// with [`ErrorNumber::OperationInvalid`] afterwards.
+///
+/// See <https://libvirt.org/html/libvirt-libvirt-event.html#virEventRegisterImpl>
+pub fn register_event_impl(event_loop: Arc<dyn EventLoop>) -> Result<(), Error> {
+    let mut guard = match EVENT_IMPL.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if guard.is_some() {
+        return Err(Error::new(
+            ErrorNumber::OperationInvalid,
+            "an event loop implementation is already registered".into(),
+        ));
+    }
+    *guard = Some(event_loop);
+    drop(guard);
+    unsafe {
+        sys::virEventRegisterImpl(
+            Some(event_impl_add_handle),
+            Some(event_impl_update_handle),
+            Some(event_impl_remove_handle),
+            Some(event_impl_add_timeout),
+            Some(event_impl_update_timeout),
+            Some(event_impl_remove_timeout),
+        )
+    };
+    Ok(())
+}
+
+struct PollHandle {
+    watch: libc::c_int,
+    fd: RawFd,
+    events: sys::virEventHandleType,
+    cb: Arc<EventHandleCallback>,
+}
+
+struct PollTimeout {
+    timer: libc::c_int,
+    frequency: libc::c_int,
+    expires_at: Option<Instant>,
+    cb: Arc<EventTimeoutCallback>,
+}
+
+#[derive(Default)]
+struct PollState {
+    next_watch: libc::c_int,
+    next_timer: libc::c_int,
+    handles: Vec<PollHandle>,
+    timeouts: Vec<PollTimeout>,
+    removed_handles: Vec<Arc<EventHandleCallback>>,
+    removed_timeouts: Vec<Arc<EventTimeoutCallback>>,
+}
+
+/// Reference [`EventLoop`] implementation based on `poll(2)`.
+///
+/// The loop is driven by calling [`PollEventLoop::run_once`]
+/// repeatedly, from any thread.
+///
+/// # Examples
+///
+/// ````no_run
+/// use std::sync::Arc;
+/// use virt::event::{register_event_impl, PollEventLoop};
+///
+/// let event_loop = Arc::new(PollEventLoop::new().unwrap());
+/// register_event_impl(event_loop.clone()).unwrap();
+/// loop {
+///     event_loop.run_once().unwrap();
+/// }
+/// ````
+pub struct PollEventLoop {
+    state: Mutex<PollState>,
+    wakeup_rx: RawFd,
+    wakeup_tx: RawFd,
+}
+
+impl Drop for PollEventLoop {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.wakeup_rx);
+            libc::close(self.wakeup_tx);
+        }
+    }
+}
+
+fn set_cloexec_nonblock(fd: RawFd) -> io::Result<()> {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let flags = libc::fcntl(fd, libc::F_GETFD);
+        if flags < 0 || libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+fn to_poll_events(events: sys::virEventHandleType) -> libc::c_short {
+    let mut ret = 0;
+    if events & sys::VIR_EVENT_HANDLE_READABLE != 0 {
+        ret |= libc::POLLIN;
+    }
+    if events & sys::VIR_EVENT_HANDLE_WRITABLE != 0 {
+        ret |= libc::POLLOUT;
+    }
+    if events & sys::VIR_EVENT_HANDLE_ERROR != 0 {
+        ret |= libc::POLLERR;
+    }
+    if events & sys::VIR_EVENT_HANDLE_HANGUP != 0 {
+        ret |= libc::POLLHUP;
+    }
+    ret
+}
+
+fn from_poll_events(revents: libc::c_short) -> sys::virEventHandleType {
+    let mut ret = 0;
+    if revents & libc::POLLIN != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_READABLE;
+    }
+    if revents & libc::POLLOUT != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_WRITABLE;
+    }
+    if revents & (libc::POLLERR | libc::POLLNVAL) != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_ERROR;
+    }
+    if revents & libc::POLLHUP != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_HANGUP;
+    }
+    ret
+}
+
+fn expiry(frequency: libc::c_int, now: Instant) -> Option<Instant> {
+    if frequency < 0 {
+        None
+    } else {
+        Some(now + Duration::from_millis(frequency as u64))
+    }
+}
+
+impl PollEventLoop {
+    pub fn new() -> io::Result<PollEventLoop> {
+        let mut fds: [RawFd; 2] = [-1; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let event_loop = PollEventLoop {
+            state: Mutex::new(PollState {
+                next_watch: 1,
+                next_timer: 1,
+                ..Default::default()
+            }),
+            wakeup_rx: fds[0],
+            wakeup_tx: fds[1],
+        };
+        set_cloexec_nonblock(event_loop.wakeup_rx)?;
+        set_cloexec_nonblock(event_loop.wakeup_tx)?;
+        Ok(event_loop)
+    }
+
+    fn state(&self) -> MutexGuard<'_, PollState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Interrupts a `run_once` call blocked in `poll(2)` so that it
+    /// picks up changes made from another thread.
+    pub fn wakeup(&self) {
+        let buf = [0u8; 1];
+        // A full pipe already guarantees a wakeup, ignore errors.
+        unsafe { libc::write(self.wakeup_tx, buf.as_ptr() as *const libc::c_void, 1) };
+    }
+
+    fn drain_wakeup(&self) {
+        let mut buf = [0u8; 64];
+        while unsafe {
+            libc::read(
+                self.wakeup_rx,
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+            )
+        } > 0
+        {}
+    }
+
+    /// Runs one iteration of the event loop: waits until a watched
+    /// file descriptor is ready or a timer expires, then dispatches
+    /// the matching callbacks.
+    pub fn run_once(&self) -> io::Result<()> {
+        let mut fds = vec![libc::pollfd {
+            fd: self.wakeup_rx,
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        let mut watches = Vec::new();
+        let timeout = {
+            let state = self.state();
+            for h in state.handles.iter().filter(|h| h.events != 0) {
+                watches.push(h.watch);
+                fds.push(libc::pollfd {
+                    fd: h.fd,
+                    events: to_poll_events(h.events),
+                    revents: 0,
+                });
+            }
+            let now = Instant::now();
+            state
+                .timeouts
+                .iter()
+                .filter_map(|t| t.expires_at)
+                .min()
+                .map_or(-1, |at| {
+                    let ms = at.saturating_duration_since(now).as_millis();
+                    ms.min(libc::c_int::MAX as u128) as libc::c_int
+                })
+        };
+
+        let ret = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                return Ok(());
+            }
+            return Err(err);
+        }
+        if fds[0].revents != 0 {
+            self.drain_wakeup();
+        }
+
+        self.dispatch_timeouts();
+        self.dispatch_handles(&watches, &fds[1..]);
+        self.release_removed();
+        Ok(())
+    }
+
+    fn release_removed(&self) {
+        // Take the callbacks out first so that libvirt's free
+        // callbacks run without the state locked.
+        let removed = {
+            let mut state = self.state();
+            (
+                mem::take(&mut state.removed_handles),
+                mem::take(&mut state.removed_timeouts),
+            )
+        };
+        drop(removed);
+    }
+
+    fn dispatch_timeouts(&self) {
+        let now = Instant::now();
+        let expired: Vec<(libc::c_int, Arc<EventTimeoutCallback>)> = {
+            let mut state = self.state();
+            state
+                .timeouts
+                .iter_mut()
+                .filter(|t| t.expires_at.map_or(false, |at| at <= now))
+                .map(|t| {
+                    t.expires_at = expiry(t.frequency, now);
+                    (t.timer, Arc::clone(&t.cb))
+                })
+                .collect()
+        };
+        for (timer, cb) in expired {
+            cb.call(timer);
+        }
+    }
+
+    fn dispatch_handles(&self, watches: &[libc::c_int], fds: &[libc::pollfd]) {
+        for (&watch, pfd) in watches.iter().zip(fds).filter(|(_, pfd)| pfd.revents != 0) {
+            // The handle may have been removed or replaced by an
+            // earlier callback of this iteration.
+            let ready = {
+                let state = self.state();
+                state
+                    .handles
+                    .iter()
+                    .find(|h| h.watch == watch && h.events != 0)
+                    .map(|h| Arc::clone(&h.cb))
+            };
+            if let Some(cb) = ready {
+                cb.call(watch, pfd.fd, from_poll_events(pfd.revents));
+            }
+        }
+    }
+}
+
+impl EventLoop for PollEventLoop {
+    fn add_handle(
+        &self,
+        fd: RawFd,
+        events: sys::virEventHandleType,
+        cb: EventHandleCallback,
+    ) -> Result<libc::c_int, EventHandleCallback> {
+        let watch = {
+            let mut state = self.state();
+            let watch = state.next_watch;
+            state.next_watch += 1;
+            state.handles.push(PollHandle {
+                watch,
+                fd,
+                events,
+                cb: Arc::new(cb),
+            });
+            watch
+        };
+        self.wakeup();
+        Ok(watch)
+    }
+
+    fn update_handle(&self, watch: libc::c_int, events: sys::virEventHandleType) {
+        if let Some(h) = self.state().handles.iter_mut().find(|h| h.watch == watch) {
+            h.events = events;
+        }
+        self.wakeup();
+    }
+
+    fn remove_handle(&self, watch: libc::c_int) -> bool {
+        let found = {
+            let mut state = self.state();
+            match state.handles.iter().position(|h| h.watch == watch) {
+                Some(i) => {
+                    // libvirt does not allow its free callback to run
+                    // from within removeHandle, defer it to the end of
+                    // the next loop iteration.
+                    let h = state.handles.remove(i);
+                    state.removed_handles.push(h.cb);
+                    true
+                }
+                None => false,
+            }
+        };
+        self.wakeup();
+        found
+    }
+
+    fn add_timeout(
+        &self,
+        timeout: libc::c_int,
+        cb: EventTimeoutCallback,
+    ) -> Result<libc::c_int, EventTimeoutCallback> {
+        let timer = {
+            let mut state = self.state();
+            let timer = state.next_timer;
+            state.next_timer += 1;
+            state.timeouts.push(PollTimeout {
+                timer,
+                frequency: timeout,
+                expires_at: expiry(timeout, Instant::now()),
+                cb: Arc::new(cb),
+            });
+            timer
+        };
+        self.wakeup();
+        Ok(timer)
+    }
+
+    fn update_timeout(&self, timer: libc::c_int, timeout: libc::c_int) {
+        if let Some(t) = self.state().timeouts.iter_mut().find(|t| t.timer == timer) {
+            t.frequency = timeout;
+            t.expires_at = expiry(timeout, Instant::now());
+        }
+        self.wakeup();
+    }
+
+    fn remove_timeout(&self, timer: libc::c_int) -> bool {
+        let found = {
+            let mut state = self.state();
+            match state.timeouts.iter().position(|t| t.timer == timer) {
+                Some(i) => {
+                    let t = state.timeouts.remove(i);
+                    state.removed_timeouts.push(t.cb);
+                    true
+                }
+                None => false,
+            }
+        };
+        self.wakeup();
+        found
+    }
+}
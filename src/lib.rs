@@ -137,11 +137,13 @@ macro_rules! typed_params_release_c_chars {
     };
 }
 
+mod callback;
 mod typedparams;
 mod util;
 
 pub mod connect;
 pub mod domain;
+pub mod domain_checkpoint;
 pub mod domain_snapshot;
 pub mod enumutil;
 pub mod error;
@@ -154,3 +156,5 @@ pub mod secret;
 pub mod storage_pool;
 pub mod storage_vol;
 pub mod stream;
+#[cfg(feature = "tokio")]
+pub mod tokio_event;
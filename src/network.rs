@@ -17,11 +17,13 @@
  */
 
 use std::ffi::CString;
-use std::str;
+use std::{mem, str};
 
 use uuid::Uuid;
 
-use crate::connect::Connect;
+use crate::callback::event_dispatch;
+use crate::connect::{Connect, EventCallback};
+use crate::enumutil::{impl_enum, Enum};
 use crate::error::Error;
 use crate::util::{check_neg, check_null};
 
@@ -216,3 +218,122 @@ impl Network {
         Ok(())
     }
 }
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum NetworkEventId {
+    Lifecycle,
+    MetadataChange,
+}
+
+pub type NetworkEventIdEnum = Enum<NetworkEventId, sys::virNetworkEventID>;
+
+impl_enum! {
+    enum: NetworkEventId,
+    raw: sys::virNetworkEventID,
+    match: {
+    sys::VIR_NETWORK_EVENT_ID_LIFECYCLE => Lifecycle,
+    sys::VIR_NETWORK_EVENT_ID_METADATA_CHANGE => MetadataChange,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum NetworkEventLifecycleType {
+    Defined,
+    Undefined,
+    Started,
+    Stopped,
+}
+
+pub type NetworkEventLifecycleTypeEnum =
+    Enum<NetworkEventLifecycleType, sys::virNetworkEventLifecycleType>;
+
+impl_enum! {
+    enum: NetworkEventLifecycleType,
+    raw: sys::virNetworkEventLifecycleType,
+    match: {
+    sys::VIR_NETWORK_EVENT_DEFINED => Defined,
+    sys::VIR_NETWORK_EVENT_UNDEFINED => Undefined,
+    sys::VIR_NETWORK_EVENT_STARTED => Started,
+    sys::VIR_NETWORK_EVENT_STOPPED => Stopped,
+    }
+}
+
+/// Event delivered to a callback registered with
+/// [`Connect::network_event_register_any`].
+#[derive(Clone, Debug)]
+pub enum NetworkEvent {
+    Lifecycle {
+        event: NetworkEventLifecycleTypeEnum,
+        /// Currently always 0, reserved for future use by libvirt.
+        detail: i32,
+    },
+    MetadataChange {
+        /// One of the `sys::VIR_NETWORK_METADATA_*` constants.
+        metadata_type: i32,
+        nsuri: Option<String>,
+    },
+}
+
+unsafe extern "C" fn network_event_lifecycle_callback<
+    F: FnMut(&Connect, &Network, NetworkEvent),
+>(
+    conn: sys::virConnectPtr,
+    net: sys::virNetworkPtr,
+    event: libc::c_int,
+    detail: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || NetworkEvent::Lifecycle {
+        event: NetworkEventLifecycleTypeEnum::from_raw(event as sys::virNetworkEventLifecycleType),
+        detail,
+    };
+    event_dispatch::<_, _, F>(conn, Network::from_ptr(net), opaque, event);
+}
+
+unsafe extern "C" fn network_event_metadata_change_callback<
+    F: FnMut(&Connect, &Network, NetworkEvent),
+>(
+    conn: sys::virConnectPtr,
+    net: sys::virNetworkPtr,
+    metadata_type: libc::c_int,
+    nsuri: *const libc::c_char,
+    opaque: *mut libc::c_void,
+) {
+    let event = || NetworkEvent::MetadataChange {
+        metadata_type,
+        nsuri: if nsuri.is_null() {
+            None
+        } else {
+            Some(c_chars_to_string!(nsuri, nofree))
+        },
+    };
+    event_dispatch::<_, _, F>(conn, Network::from_ptr(net), opaque, event);
+}
+
+/// Returns the C callback matching the signature libvirt uses for
+/// `event_id`, cast to the generic callback type expected by
+/// virConnectNetworkEventRegisterAny.
+pub(crate) fn network_event_callback<F: FnMut(&Connect, &Network, NetworkEvent)>(
+    event_id: NetworkEventId,
+) -> sys::virConnectNetworkEventGenericCallback {
+    // Safe because libvirt casts the generic callback back to the
+    // signature matching event_id before invoking it.
+    unsafe {
+        match event_id {
+            NetworkEventId::Lifecycle => mem::transmute::<
+                sys::virConnectNetworkEventLifecycleCallback,
+                sys::virConnectNetworkEventGenericCallback,
+            >(Some(network_event_lifecycle_callback::<F>)),
+            NetworkEventId::MetadataChange => {
+                mem::transmute::<
+                    sys::virConnectNetworkEventMetadataChangeCallback,
+                    sys::virConnectNetworkEventGenericCallback,
+                >(Some(network_event_metadata_change_callback::<F>))
+            }
+        }
+    }
+}
+
+/// Registration of a network event callback, returned by
+/// [`Connect::network_event_register_any`].
+pub type NetworkEventCallback = EventCallback;
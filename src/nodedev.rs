@@ -17,8 +17,11 @@
  */
 
 use std::ffi::CString;
-use std::{ptr, str};
+use std::{mem, ptr, str};
 
+use crate::callback::event_dispatch;
+use crate::connect::{Connect, EventCallback};
+use crate::enumutil::{impl_enum, Enum};
 use crate::error::Error;
 use crate::util::{check_neg, check_null};
 
@@ -174,3 +177,107 @@ impl NodeDevice {
         Ok(array)
     }
 }
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum NodeDeviceEventId {
+    Lifecycle,
+    Update,
+}
+
+pub type NodeDeviceEventIdEnum = Enum<NodeDeviceEventId, sys::virNodeDeviceEventID>;
+
+impl_enum! {
+    enum: NodeDeviceEventId,
+    raw: sys::virNodeDeviceEventID,
+    match: {
+    sys::VIR_NODE_DEVICE_EVENT_ID_LIFECYCLE => Lifecycle,
+    sys::VIR_NODE_DEVICE_EVENT_ID_UPDATE => Update,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum NodeDeviceEventLifecycleType {
+    Created,
+    Deleted,
+    Defined,
+    Undefined,
+}
+
+pub type NodeDeviceEventLifecycleTypeEnum =
+    Enum<NodeDeviceEventLifecycleType, sys::virNodeDeviceEventLifecycleType>;
+
+impl_enum! {
+    enum: NodeDeviceEventLifecycleType,
+    raw: sys::virNodeDeviceEventLifecycleType,
+    match: {
+    sys::VIR_NODE_DEVICE_EVENT_CREATED => Created,
+    sys::VIR_NODE_DEVICE_EVENT_DELETED => Deleted,
+    sys::VIR_NODE_DEVICE_EVENT_DEFINED => Defined,
+    sys::VIR_NODE_DEVICE_EVENT_UNDEFINED => Undefined,
+    }
+}
+
+/// Event delivered to a callback registered with
+/// [`Connect::node_device_event_register_any`].
+#[derive(Clone, Debug)]
+pub enum NodeDeviceEvent {
+    Lifecycle {
+        event: NodeDeviceEventLifecycleTypeEnum,
+        /// Currently always 0, reserved for future use by libvirt.
+        detail: i32,
+    },
+    Update,
+}
+
+unsafe extern "C" fn node_device_event_lifecycle_callback<
+    F: FnMut(&Connect, &NodeDevice, NodeDeviceEvent),
+>(
+    conn: sys::virConnectPtr,
+    dev: sys::virNodeDevicePtr,
+    event: libc::c_int,
+    detail: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || NodeDeviceEvent::Lifecycle {
+        event: NodeDeviceEventLifecycleTypeEnum::from_raw(
+            event as sys::virNodeDeviceEventLifecycleType,
+        ),
+        detail,
+    };
+    event_dispatch::<_, _, F>(conn, NodeDevice::from_ptr(dev), opaque, event);
+}
+
+unsafe extern "C" fn node_device_event_update_callback<
+    F: FnMut(&Connect, &NodeDevice, NodeDeviceEvent),
+>(
+    conn: sys::virConnectPtr,
+    dev: sys::virNodeDevicePtr,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, NodeDevice::from_ptr(dev), opaque, || {
+        NodeDeviceEvent::Update
+    });
+}
+
+/// Returns the C callback matching the signature libvirt uses for
+/// `event_id`, cast to the generic callback type expected by
+/// virConnectNodeDeviceEventRegisterAny.
+pub(crate) fn node_device_event_callback<F: FnMut(&Connect, &NodeDevice, NodeDeviceEvent)>(
+    event_id: NodeDeviceEventId,
+) -> sys::virConnectNodeDeviceEventGenericCallback {
+    match event_id {
+        // Safe because libvirt casts the generic callback back to the
+        // lifecycle signature before invoking it.
+        NodeDeviceEventId::Lifecycle => unsafe {
+            mem::transmute::<
+                sys::virConnectNodeDeviceEventLifecycleCallback,
+                sys::virConnectNodeDeviceEventGenericCallback,
+            >(Some(node_device_event_lifecycle_callback::<F>))
+        },
+        NodeDeviceEventId::Update => Some(node_device_event_update_callback::<F>),
+    }
+}
+
+/// Registration of a node device event callback, returned by
+/// [`Connect::node_device_event_register_any`].
+pub type NodeDeviceEventCallback = EventCallback;
@@ -16,9 +16,13 @@
  * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
  */
 
+use std::mem;
+
 use uuid::Uuid;
 
-use crate::connect::Connect;
+use crate::callback::event_dispatch;
+use crate::connect::{Connect, EventCallback};
+use crate::enumutil::{impl_enum, Enum};
 use crate::error::Error;
 use crate::util::{check_neg, check_null};
 
@@ -163,3 +167,99 @@ impl Secret {
         Ok(())
     }
 }
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SecretEventId {
+    Lifecycle,
+    ValueChanged,
+}
+
+pub type SecretEventIdEnum = Enum<SecretEventId, sys::virSecretEventID>;
+
+impl_enum! {
+    enum: SecretEventId,
+    raw: sys::virSecretEventID,
+    match: {
+    sys::VIR_SECRET_EVENT_ID_LIFECYCLE => Lifecycle,
+    sys::VIR_SECRET_EVENT_ID_VALUE_CHANGED => ValueChanged,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SecretEventLifecycleType {
+    Defined,
+    Undefined,
+}
+
+pub type SecretEventLifecycleTypeEnum =
+    Enum<SecretEventLifecycleType, sys::virSecretEventLifecycleType>;
+
+impl_enum! {
+    enum: SecretEventLifecycleType,
+    raw: sys::virSecretEventLifecycleType,
+    match: {
+    sys::VIR_SECRET_EVENT_DEFINED => Defined,
+    sys::VIR_SECRET_EVENT_UNDEFINED => Undefined,
+    }
+}
+
+/// Event delivered to a callback registered with
+/// [`Connect::secret_event_register_any`].
+#[derive(Clone, Debug)]
+pub enum SecretEvent {
+    Lifecycle {
+        event: SecretEventLifecycleTypeEnum,
+        /// Currently always 0, reserved for future use by libvirt.
+        detail: i32,
+    },
+    ValueChanged,
+}
+
+unsafe extern "C" fn secret_event_lifecycle_callback<F: FnMut(&Connect, &Secret, SecretEvent)>(
+    conn: sys::virConnectPtr,
+    secret: sys::virSecretPtr,
+    event: libc::c_int,
+    detail: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || SecretEvent::Lifecycle {
+        event: SecretEventLifecycleTypeEnum::from_raw(event as sys::virSecretEventLifecycleType),
+        detail,
+    };
+    event_dispatch::<_, _, F>(conn, Secret::from_ptr(secret), opaque, event);
+}
+
+unsafe extern "C" fn secret_event_value_changed_callback<
+    F: FnMut(&Connect, &Secret, SecretEvent),
+>(
+    conn: sys::virConnectPtr,
+    secret: sys::virSecretPtr,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, Secret::from_ptr(secret), opaque, || {
+        SecretEvent::ValueChanged
+    });
+}
+
+/// Returns the C callback matching the signature libvirt uses for
+/// `event_id`, cast to the generic callback type expected by
+/// virConnectSecretEventRegisterAny.
+pub(crate) fn secret_event_callback<F: FnMut(&Connect, &Secret, SecretEvent)>(
+    event_id: SecretEventId,
+) -> sys::virConnectSecretEventGenericCallback {
+    match event_id {
+        // Safe because libvirt casts the generic callback back to the
+        // lifecycle signature before invoking it.
+        SecretEventId::Lifecycle => unsafe {
+            mem::transmute::<
+                sys::virConnectSecretEventLifecycleCallback,
+                sys::virConnectSecretEventGenericCallback,
+            >(Some(secret_event_lifecycle_callback::<F>))
+        },
+        SecretEventId::ValueChanged => Some(secret_event_value_changed_callback::<F>),
+    }
+}
+
+/// Registration of a secret event callback, returned by
+/// [`Connect::secret_event_register_any`].
+pub type SecretEventCallback = EventCallback;
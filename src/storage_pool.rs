@@ -21,7 +21,9 @@ use std::{mem, ptr};
 
 use uuid::Uuid;
 
-use crate::connect::Connect;
+use crate::callback::event_dispatch;
+use crate::connect::{Connect, EventCallback};
+use crate::enumutil::{impl_enum, Enum};
 use crate::error::Error;
 use crate::storage_vol::StorageVol;
 use crate::util::{check_neg, check_null};
@@ -303,3 +305,111 @@ impl StoragePool {
         Ok(unsafe { StoragePoolInfo::from_ptr(&mut pinfo.assume_init()) })
     }
 }
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum StoragePoolEventId {
+    Lifecycle,
+    Refresh,
+}
+
+pub type StoragePoolEventIdEnum = Enum<StoragePoolEventId, sys::virStoragePoolEventID>;
+
+impl_enum! {
+    enum: StoragePoolEventId,
+    raw: sys::virStoragePoolEventID,
+    match: {
+    sys::VIR_STORAGE_POOL_EVENT_ID_LIFECYCLE => Lifecycle,
+    sys::VIR_STORAGE_POOL_EVENT_ID_REFRESH => Refresh,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum StoragePoolEventLifecycleType {
+    Defined,
+    Undefined,
+    Started,
+    Stopped,
+    Created,
+    Deleted,
+}
+
+pub type StoragePoolEventLifecycleTypeEnum =
+    Enum<StoragePoolEventLifecycleType, sys::virStoragePoolEventLifecycleType>;
+
+impl_enum! {
+    enum: StoragePoolEventLifecycleType,
+    raw: sys::virStoragePoolEventLifecycleType,
+    match: {
+    sys::VIR_STORAGE_POOL_EVENT_DEFINED => Defined,
+    sys::VIR_STORAGE_POOL_EVENT_UNDEFINED => Undefined,
+    sys::VIR_STORAGE_POOL_EVENT_STARTED => Started,
+    sys::VIR_STORAGE_POOL_EVENT_STOPPED => Stopped,
+    sys::VIR_STORAGE_POOL_EVENT_CREATED => Created,
+    sys::VIR_STORAGE_POOL_EVENT_DELETED => Deleted,
+    }
+}
+
+/// Event delivered to a callback registered with
+/// [`Connect::storage_pool_event_register_any`].
+#[derive(Clone, Debug)]
+pub enum StoragePoolEvent {
+    Lifecycle {
+        event: StoragePoolEventLifecycleTypeEnum,
+        /// Currently always 0, reserved for future use by libvirt.
+        detail: i32,
+    },
+    Refresh,
+}
+
+unsafe extern "C" fn storage_pool_event_lifecycle_callback<
+    F: FnMut(&Connect, &StoragePool, StoragePoolEvent),
+>(
+    conn: sys::virConnectPtr,
+    pool: sys::virStoragePoolPtr,
+    event: libc::c_int,
+    detail: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let event = || StoragePoolEvent::Lifecycle {
+        event: StoragePoolEventLifecycleTypeEnum::from_raw(
+            event as sys::virStoragePoolEventLifecycleType,
+        ),
+        detail,
+    };
+    event_dispatch::<_, _, F>(conn, StoragePool::from_ptr(pool), opaque, event);
+}
+
+unsafe extern "C" fn storage_pool_event_refresh_callback<
+    F: FnMut(&Connect, &StoragePool, StoragePoolEvent),
+>(
+    conn: sys::virConnectPtr,
+    pool: sys::virStoragePoolPtr,
+    opaque: *mut libc::c_void,
+) {
+    event_dispatch::<_, _, F>(conn, StoragePool::from_ptr(pool), opaque, || {
+        StoragePoolEvent::Refresh
+    });
+}
+
+/// Returns the C callback matching the signature libvirt uses for
+/// `event_id`, cast to the generic callback type expected by
+/// virConnectStoragePoolEventRegisterAny.
+pub(crate) fn storage_pool_event_callback<F: FnMut(&Connect, &StoragePool, StoragePoolEvent)>(
+    event_id: StoragePoolEventId,
+) -> sys::virConnectStoragePoolEventGenericCallback {
+    match event_id {
+        // Safe because libvirt casts the generic callback back to the
+        // lifecycle signature before invoking it.
+        StoragePoolEventId::Lifecycle => unsafe {
+            mem::transmute::<
+                sys::virConnectStoragePoolEventLifecycleCallback,
+                sys::virConnectStoragePoolEventGenericCallback,
+            >(Some(storage_pool_event_lifecycle_callback::<F>))
+        },
+        StoragePoolEventId::Refresh => Some(storage_pool_event_refresh_callback::<F>),
+    }
+}
+
+/// Registration of a storage pool event callback, returned by
+/// [`Connect::storage_pool_event_register_any`].
+pub type StoragePoolEventCallback = EventCallback;
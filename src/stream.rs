@@ -16,33 +16,205 @@
  * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
  */
 
+use std::any::Any;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::unix::io::AsRawFd;
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+#[cfg(feature = "futures")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll, Waker};
+use std::{mem, slice};
+
+use crate::callback::{abort_on_panic, callback_free};
 use crate::connect::Connect;
-use crate::error::Error;
+use crate::error::{Error, ErrorNumber};
 use crate::util::{check_neg, check_null};
 
 // wrapper for callbacks
-extern "C" fn event_callback(c: sys::virStreamPtr, flags: libc::c_int, opaque: *mut libc::c_void) {
-    let flags = flags as sys::virStreamFlags;
-    let shadow_self = unsafe { &mut *(opaque as *mut Stream) };
-    if let Some(callback) = &mut shadow_self.callback {
-        callback(
-            unsafe {
-                sys::virStreamRef(c);
-                &Stream::from_ptr(c)
-            },
-            flags,
-        );
+unsafe extern "C" fn event_callback<F: FnMut(&Stream, sys::virStreamEventType)>(
+    c: sys::virStreamPtr,
+    events: libc::c_int,
+    opaque: *mut libc::c_void,
+) {
+    let cb = &mut *(opaque as *mut F);
+    // libvirt only lends its reference for the duration of the call.
+    let stream = mem::ManuallyDrop::new(Stream::from_ptr(c));
+    abort_on_panic(|| cb(&stream, events as sys::virStreamEventType));
+}
+
+// Removes the stream callback when a pending wait is resolved or
+// cancelled.
+#[cfg(feature = "tokio")]
+struct RemoveCallbackGuard<'a>(&'a Stream);
+
+#[cfg(feature = "tokio")]
+impl Drop for RemoveCallbackGuard<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.event_remove_callback();
     }
 }
 
-extern "C" fn event_free(_opaque: *mut libc::c_void) {}
+// Closures handed over to the virStream*All functions, the first
+// error they return is kept to be reported instead of the generic
+// libvirt one. Panics cannot unwind through libvirt, they are held
+// until it returns and resumed from there.
+struct StreamHandlers<D, H, S> {
+    data: D,
+    hole: H,
+    skip: S,
+    err: Option<io::Error>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<D, H, S> StreamHandlers<D, H, S> {
+    fn new(data: D, hole: H, skip: S) -> StreamHandlers<D, H, S> {
+        StreamHandlers {
+            data,
+            hole,
+            skip,
+            err: None,
+            panic: None,
+        }
+    }
 
-type StreamCallback = dyn FnMut(&Stream, sys::virStreamEventType);
+    fn check(&mut self, ret: libc::c_int) -> Result<(), Error> {
+        if let Some(payload) = self.panic.take() {
+            panic::resume_unwind(payload);
+        }
+        if ret == -1 {
+            return Err(match self.err.take() {
+                Some(e) => callback_error(e),
+                None => Error::last_error(),
+            });
+        }
+        Ok(())
+    }
+
+    fn run<T>(
+        &mut self,
+        call: impl FnOnce(&mut Self) -> io::Result<T>,
+        f: impl FnOnce(T) -> libc::c_int,
+    ) -> libc::c_int {
+        match panic::catch_unwind(AssertUnwindSafe(|| call(self))) {
+            Ok(Ok(v)) => f(v),
+            Ok(Err(e)) => {
+                self.err = Some(e);
+                -1
+            }
+            Err(payload) => {
+                self.panic = Some(payload);
+                -1
+            }
+        }
+    }
+}
+
+// Keeps libvirt errors forwarded through io::Error as they were.
+fn callback_error(err: io::Error) -> Error {
+    match err.get_ref().and_then(|e| e.downcast_ref::<Error>()) {
+        Some(e) => e.clone(),
+        None => Error::new(ErrorNumber::SystemError, err.to_string()),
+    }
+}
+
+unsafe extern "C" fn stream_sink<D: FnMut(&[u8]) -> io::Result<usize>, H, S>(
+    _st: sys::virStreamPtr,
+    data: *const libc::c_char,
+    nbytes: usize,
+    opaque: *mut libc::c_void,
+) -> libc::c_int {
+    let handlers = &mut *(opaque as *mut StreamHandlers<D, H, S>);
+    let buf = slice::from_raw_parts(data as *const u8, nbytes);
+    handlers.run(|h| (h.data)(buf), |n| n as libc::c_int)
+}
+
+unsafe extern "C" fn stream_sink_hole<D, H: FnMut(i64) -> io::Result<()>, S>(
+    _st: sys::virStreamPtr,
+    length: libc::c_longlong,
+    opaque: *mut libc::c_void,
+) -> libc::c_int {
+    let handlers = &mut *(opaque as *mut StreamHandlers<D, H, S>);
+    handlers.run(|h| (h.hole)(length), |()| 0)
+}
+
+unsafe extern "C" fn stream_source<D: FnMut(&mut [u8]) -> io::Result<usize>, H, S>(
+    _st: sys::virStreamPtr,
+    data: *mut libc::c_char,
+    nbytes: usize,
+    opaque: *mut libc::c_void,
+) -> libc::c_int {
+    let handlers = &mut *(opaque as *mut StreamHandlers<D, H, S>);
+    let buf = slice::from_raw_parts_mut(data as *mut u8, nbytes);
+    handlers.run(|h| (h.data)(buf), |n| n as libc::c_int)
+}
+
+unsafe extern "C" fn stream_source_hole<D, H: FnMut() -> io::Result<(bool, i64)>, S>(
+    _st: sys::virStreamPtr,
+    in_data: *mut libc::c_int,
+    length: *mut libc::c_longlong,
+    opaque: *mut libc::c_void,
+) -> libc::c_int {
+    let handlers = &mut *(opaque as *mut StreamHandlers<D, H, S>);
+    handlers.run(
+        |h| (h.hole)(),
+        |(data, len)| {
+            *in_data = data as libc::c_int;
+            *length = len;
+            0
+        },
+    )
+}
+
+unsafe extern "C" fn stream_source_skip<D, H, S: FnMut(i64) -> io::Result<()>>(
+    _st: sys::virStreamPtr,
+    length: libc::c_longlong,
+    opaque: *mut libc::c_void,
+) -> libc::c_int {
+    let handlers = &mut *(opaque as *mut StreamHandlers<D, H, S>);
+    handlers.run(|h| (h.skip)(length), |()| 0)
+}
+
+#[cfg(not(target_os = "linux"))]
+use libc::lseek;
+#[cfg(target_os = "linux")]
+use libc::lseek64 as lseek;
+
+// Returns whether the current position of file is in a data section
+// or a hole, and how many bytes are left in that section. The file
+// position is left untouched.
+fn file_in_data(file: &File) -> io::Result<(bool, i64)> {
+    let fd = file.as_raw_fd();
+    let seek = |offset: i64, whence: libc::c_int| -> io::Result<i64> {
+        match unsafe { lseek(fd, offset as _, whence) } {
+            -1 => Err(io::Error::last_os_error()),
+            pos => Ok(pos),
+        }
+    };
+    let cur = seek(0, libc::SEEK_CUR)?;
+    let ret = match seek(cur, libc::SEEK_DATA) {
+        // Past the last data section, in a trailing hole or at EOF.
+        Err(e) if e.raw_os_error() == Some(libc::ENXIO) => {
+            let end = seek(0, libc::SEEK_END)?;
+            (false, end - cur)
+        }
+        Err(e) => return Err(e),
+        Ok(data) if data > cur => (false, data - cur),
+        Ok(_) => {
+            let hole = seek(cur, libc::SEEK_HOLE)?;
+            (true, hole - cur)
+        }
+    };
+    seek(cur, libc::SEEK_SET)?;
+    Ok(ret)
+}
 
 // #[derive(Debug)]
 pub struct Stream {
     ptr: sys::virStreamPtr,
-    callback: Option<Box<StreamCallback>>,
 }
 
 unsafe impl Send for Stream {}
@@ -50,11 +222,6 @@ unsafe impl Sync for Stream {}
 
 impl Drop for Stream {
     fn drop(&mut self) {
-        if self.callback.is_some() {
-            if let Err(e) = self.event_remove_callback() {
-                panic!("Unable to remove event callback for Stream: {e}")
-            }
-        }
         if let Err(e) = check_neg!(unsafe { sys::virStreamFree(self.as_ptr()) }) {
             panic!("Unable to drop reference on stream: {e}")
         }
@@ -89,10 +256,7 @@ impl Stream {
     /// The rust wrapper will own the reference count
     /// for the C object upon return.
     pub unsafe fn from_ptr(ptr: sys::virStreamPtr) -> Stream {
-        Stream {
-            ptr,
-            callback: None,
-        }
+        Stream { ptr }
     }
 
     /// # Safety
@@ -151,25 +315,218 @@ impl Stream {
         Ok(ret as isize)
     }
 
+    /// Sends the whole stream, calling `data` to fill the buffer
+    /// until it returns 0.
+    ///
+    /// On failure the stream is aborted, errors returned by `data`
+    /// are reported as is.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamSendAll>
+    pub fn send_all<D: FnMut(&mut [u8]) -> io::Result<usize>>(&self, data: D) -> Result<(), Error> {
+        let mut handlers = StreamHandlers::new(data, (), ());
+        let ret = unsafe {
+            sys::virStreamSendAll(
+                self.as_ptr(),
+                Some(stream_source::<D, (), ()>),
+                &mut handlers as *mut _ as *mut libc::c_void,
+            )
+        };
+        handlers.check(ret)
+    }
+
+    /// Receives the whole stream, passing data to `data` which
+    /// returns the number of bytes it consumed.
+    ///
+    /// On failure the stream is aborted, errors returned by `data`
+    /// are reported as is.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamRecvAll>
+    pub fn recv_all<D: FnMut(&[u8]) -> io::Result<usize>>(&self, data: D) -> Result<(), Error> {
+        let mut handlers = StreamHandlers::new(data, (), ());
+        let ret = unsafe {
+            sys::virStreamRecvAll(
+                self.as_ptr(),
+                Some(stream_sink::<D, (), ()>),
+                &mut handlers as *mut _ as *mut libc::c_void,
+            )
+        };
+        handlers.check(ret)
+    }
+
+    /// Receive data from the stream, with `flags` a bitwise-or of
+    /// `sys::VIR_STREAM_RECV_*` values.
+    ///
+    /// Returns -2 when a non-blocking stream has no data available
+    /// yet, and -3 when `sys::VIR_STREAM_RECV_STOP_AT_HOLE` is set and
+    /// the stream reached a hole, to be read with
+    /// [`Self::recv_hole`].
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamRecvFlags>
+    pub fn recv_flags(&self, buf: &mut [u8], flags: u32) -> Result<isize, Error> {
+        let ret = check_neg!(unsafe {
+            sys::virStreamRecvFlags(
+                self.as_ptr(),
+                buf.as_mut_ptr() as *mut libc::c_char,
+                buf.len(),
+                flags as libc::c_uint,
+            )
+        })?;
+        Ok(ret as isize)
+    }
+
+    /// Skip `length` bytes of the stream, recreating a hole on the
+    /// receiving side of a sparse stream.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamSendHole>
+    pub fn send_hole(&self, length: i64, flags: u32) -> Result<(), Error> {
+        let _ = check_neg!(unsafe {
+            sys::virStreamSendHole(self.as_ptr(), length, flags as libc::c_uint)
+        })?;
+        Ok(())
+    }
+
+    /// Returns the length of the hole the stream is currently in.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamRecvHole>
+    pub fn recv_hole(&self, flags: u32) -> Result<i64, Error> {
+        let mut length: libc::c_longlong = 0;
+        let _ = check_neg!(unsafe {
+            sys::virStreamRecvHole(self.as_ptr(), &mut length, flags as libc::c_uint)
+        })?;
+        Ok(length)
+    }
+
+    /// Receives the whole sparse stream, passing data to `data`,
+    /// which returns the number of bytes it consumed, and the length
+    /// of holes to `hole`.
+    ///
+    /// On failure the stream is aborted, errors returned by the
+    /// closures are reported as is.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamSparseRecvAll>
+    pub fn sparse_recv_all<D, H>(&self, data: D, hole: H) -> Result<(), Error>
+    where
+        D: FnMut(&[u8]) -> io::Result<usize>,
+        H: FnMut(i64) -> io::Result<()>,
+    {
+        let mut handlers = StreamHandlers::new(data, hole, ());
+        let ret = unsafe {
+            sys::virStreamSparseRecvAll(
+                self.as_ptr(),
+                Some(stream_sink::<D, H, ()>),
+                Some(stream_sink_hole::<D, H, ()>),
+                &mut handlers as *mut _ as *mut libc::c_void,
+            )
+        };
+        handlers.check(ret)
+    }
+
+    /// Sends a whole sparse stream. `data` fills the buffer and
+    /// returns the number of bytes written, 0 at the end of the
+    /// stream. `in_data` tells whether the current position is in a
+    /// data section or in a hole, along with the number of bytes left
+    /// in it, and `skip` moves the position past a hole.
+    ///
+    /// On failure the stream is aborted, errors returned by the
+    /// closures are reported as is.
+    ///
+    /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamSparseSendAll>
+    pub fn sparse_send_all<D, H, S>(&self, data: D, in_data: H, skip: S) -> Result<(), Error>
+    where
+        D: FnMut(&mut [u8]) -> io::Result<usize>,
+        H: FnMut() -> io::Result<(bool, i64)>,
+        S: FnMut(i64) -> io::Result<()>,
+    {
+        let mut handlers = StreamHandlers::new(data, in_data, skip);
+        let ret = unsafe {
+            sys::virStreamSparseSendAll(
+                self.as_ptr(),
+                Some(stream_source::<D, H, S>),
+                Some(stream_source_hole::<D, H, S>),
+                Some(stream_source_skip::<D, H, S>),
+                &mut handlers as *mut _ as *mut libc::c_void,
+            )
+        };
+        handlers.check(ret)
+    }
+
+    /// Receives a sparse stream into `file` from its current
+    /// position, seeking over holes instead of writing zeroes.
+    ///
+    /// The stream is usually obtained from
+    /// [`crate::storage_vol::StorageVol::download`] with
+    /// `sys::VIR_STORAGE_VOL_DOWNLOAD_SPARSE_STREAM`.
+    pub fn sparse_recv_file(&self, file: &File) -> Result<(), Error> {
+        self.sparse_recv_all(
+            |buf| {
+                let mut f = file;
+                f.write_all(buf).map(|()| buf.len())
+            },
+            |length| {
+                let mut f = file;
+                f.seek(SeekFrom::Current(length)).map(|_| ())
+            },
+        )?;
+        // A trailing hole is not allocated by seeking past the end of
+        // the file, extend it to the right size.
+        let mut f = file;
+        let res = f.stream_position().and_then(|pos| match file.metadata() {
+            Ok(m) if m.len() < pos => file.set_len(pos),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        });
+        res.map_err(callback_error)
+    }
+
+    /// Sends `file` from its current position as a sparse stream,
+    /// finding holes with `SEEK_DATA` and `SEEK_HOLE`.
+    ///
+    /// The stream is usually obtained from
+    /// [`crate::storage_vol::StorageVol::upload`] with
+    /// `sys::VIR_STORAGE_VOL_UPLOAD_SPARSE_STREAM`.
+    pub fn sparse_send_file(&self, file: &File) -> Result<(), Error> {
+        self.sparse_send_all(
+            |buf| {
+                let mut f = file;
+                f.read(buf)
+            },
+            || file_in_data(file),
+            |length| {
+                let mut f = file;
+                f.seek(SeekFrom::Current(length)).map(|_| ())
+            },
+        )
+    }
+
     /// Add a stream event callback
     ///
+    /// The callback belongs to the underlying libvirt stream: it is
+    /// shared by all clones of this object and stays registered
+    /// until [`Self::event_remove_callback`] is called or the stream
+    /// is finished, aborted or released.
+    ///
     /// See <https://libvirt.org/html/libvirt-libvirt-stream.html#virStreamEventAddCallback>
-    pub fn event_add_callback<F: 'static + FnMut(&Stream, sys::virStreamEventType)>(
-        &mut self,
+    pub fn event_add_callback<F: 'static + Send + FnMut(&Stream, sys::virStreamEventType)>(
+        &self,
         events: sys::virStreamEventType,
         cb: F,
     ) -> Result<(), Error> {
-        let _ = check_neg!(unsafe {
-            let ptr = self as *mut _ as *mut _;
+        let opaque = Box::into_raw(Box::new(cb)) as *mut libc::c_void;
+        let ret = unsafe {
             sys::virStreamEventAddCallback(
                 self.as_ptr(),
                 events as libc::c_int,
-                Some(event_callback),
-                ptr,
-                Some(event_free),
+                Some(event_callback::<F>),
+                opaque,
+                Some(callback_free::<F>),
             )
-        })?;
-        self.callback = Some(Box::new(cb));
+        };
+        if ret == -1 {
+            let err = Error::last_error();
+            // libvirt does not call the free callback on failure.
+            unsafe { callback_free::<F>(opaque) };
+            return Err(err);
+        }
         Ok(())
     }
 
@@ -190,4 +547,339 @@ impl Stream {
         let _ = check_neg!(unsafe { sys::virStreamEventRemoveCallback(self.as_ptr()) })?;
         Ok(())
     }
+
+    /// Waits until one of `events` occurs on the stream and returns
+    /// the events that fired.
+    ///
+    /// The stream must have been created with
+    /// `sys::VIR_STREAM_NONBLOCK` and no other event callback may be
+    /// registered. Requires the `tokio` feature, events are
+    /// dispatched by [`crate::tokio_event::TokioEventLoop`] or any
+    /// other running event loop implementation.
+    #[cfg(feature = "tokio")]
+    pub async fn wait_events(
+        &self,
+        events: sys::virStreamEventType,
+    ) -> Result<sys::virStreamEventType, Error> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let mut tx = Some(tx);
+        self.event_add_callback(events, move |_, fired| {
+            if let Some(tx) = tx.take() {
+                let _ = tx.send(fired);
+            }
+        })?;
+        let _guard = RemoveCallbackGuard(self);
+        rx.await.map_err(|_| {
+            Error::new(
+                ErrorNumber::InternalError,
+                "stream callback released before any event".into(),
+            )
+        })
+    }
+}
+
+// Converts the result of a blocking send or recv call, where -2
+// means the stream is non-blocking and no data is available yet.
+fn io_result(ret: Result<isize, Error>) -> io::Result<usize> {
+    match ret {
+        Ok(-2) => Err(io::ErrorKind::WouldBlock.into()),
+        Ok(n) => Ok(n as usize),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reads data from the stream with [`Stream::recv`].
+///
+/// Non-blocking streams report [`io::ErrorKind::WouldBlock`] when no
+/// data is available yet.
+///
+/// # Examples
+///
+/// ````no_run
+/// use std::fs::File;
+/// use std::io;
+/// use virt::connect::Connect;
+/// use virt::stream::Stream;
+///
+/// let conn = Connect::open(Some("test:///default")).unwrap();
+/// let dom = conn.lookup_domain_by_name("test").unwrap();
+/// let mut stream = Stream::new(&conn, 0).unwrap();
+/// dom.screenshot(&stream, 0, 0).unwrap();
+/// io::copy(&mut stream, &mut File::create("screenshot.png").unwrap()).unwrap();
+/// stream.finish().unwrap();
+/// ````
+impl io::Read for &Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        io_result(self.recv(buf))
+    }
+}
+
+impl io::Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+/// Sends data to the stream with [`Stream::send`].
+///
+/// Non-blocking streams report [`io::ErrorKind::WouldBlock`] when
+/// the data cannot be queued yet. [`Stream::finish`] must be called
+/// once all data was written.
+impl io::Write for &Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io_result(self.send(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "futures")]
+#[derive(Default)]
+struct AsyncStreamState {
+    events: sys::virStreamEventType,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+}
+
+/// Non-blocking [`Stream`] implementing the `futures` `AsyncRead` and
+/// `AsyncWrite` traits, as well as the tokio ones when the `tokio`
+/// feature is enabled.
+///
+/// Readiness is reported by a stream event callback, which requires
+/// a running event loop implementation such as
+/// [`crate::tokio_event::TokioEventLoop`]. Requires the `futures`
+/// feature.
+#[cfg(feature = "futures")]
+pub struct AsyncStream {
+    stream: Stream,
+    state: Arc<Mutex<AsyncStreamState>>,
+    finished: bool,
+}
+
+#[cfg(feature = "futures")]
+impl AsyncStream {
+    /// Wraps `stream`, which must have been created with
+    /// `sys::VIR_STREAM_NONBLOCK` and have no event callback
+    /// registered.
+    pub fn new(stream: Stream) -> Result<AsyncStream, Error> {
+        let state = Arc::new(Mutex::new(AsyncStreamState::default()));
+        let cb_state = Arc::clone(&state);
+        stream.event_add_callback(0, move |st, fired| {
+            let mut state = match cb_state.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            let failed = fired & (sys::VIR_STREAM_EVENT_ERROR | sys::VIR_STREAM_EVENT_HANGUP) != 0;
+            if failed || fired & sys::VIR_STREAM_EVENT_READABLE != 0 {
+                state.events &= !sys::VIR_STREAM_EVENT_READABLE;
+                if let Some(waker) = state.read_waker.take() {
+                    waker.wake();
+                }
+            }
+            if failed || fired & sys::VIR_STREAM_EVENT_WRITABLE != 0 {
+                state.events &= !sys::VIR_STREAM_EVENT_WRITABLE;
+                if let Some(waker) = state.write_waker.take() {
+                    waker.wake();
+                }
+            }
+            let _ = st.event_update_callback(state.events);
+        })?;
+        Ok(AsyncStream {
+            stream,
+            state,
+            finished: false,
+        })
+    }
+
+    /// Returns the wrapped stream.
+    pub fn stream(&self) -> &Stream {
+        &self.stream
+    }
+
+    // Asks to be woken up once `event` is ready. libvirt does not
+    // invoke stream callbacks with the stream locked, so keeping the
+    // state locked while updating the callback is safe and keeps
+    // concurrent updates ordered.
+    fn wait_for(&self, cx: &Context<'_>, event: sys::virStreamEventType) -> io::Result<()> {
+        let mut state = match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let waker = Some(cx.waker().clone());
+        if event == sys::VIR_STREAM_EVENT_READABLE {
+            state.read_waker = waker;
+        } else {
+            state.write_waker = waker;
+        }
+        state.events |= event;
+        self.stream
+            .event_update_callback(state.events)
+            .map_err(io::Error::from)
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        match self.stream.recv(buf) {
+            Ok(-2) => {
+                self.wait_for(cx, sys::VIR_STREAM_EVENT_READABLE)?;
+                Poll::Pending
+            }
+            Ok(n) => Poll::Ready(Ok(n as usize)),
+            Err(e) => Poll::Ready(Err(e.into())),
+        }
+    }
+
+    fn poll_send(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.stream.send(buf) {
+            Ok(-2) => {
+                self.wait_for(cx, sys::VIR_STREAM_EVENT_WRITABLE)?;
+                Poll::Pending
+            }
+            Ok(n) => Poll::Ready(Ok(n as usize)),
+            Err(e) => Poll::Ready(Err(e.into())),
+        }
+    }
+
+    // Completes the transfer, this waits for libvirt to acknowledge
+    // the end of the stream.
+    fn poll_finish(&mut self) -> Poll<io::Result<()>> {
+        if !self.finished {
+            check_neg!(unsafe { sys::virStreamFinish(self.stream.as_ptr()) })
+                .map_err(io::Error::from)?;
+            self.finished = true;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures")]
+impl Drop for AsyncStream {
+    fn drop(&mut self) {
+        // Clones of the stream may outlive the wrapper.
+        let _ = self.stream.event_remove_callback();
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_io::AsyncRead for AsyncStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_recv(cx, buf)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_io::AsyncWrite for AsyncStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_send(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_finish()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for AsyncStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut().poll_recv(cx, buf.initialize_unfilled()) {
+            Poll::Ready(Ok(n)) => {
+                buf.advance(n);
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncWrite for AsyncStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_send(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::os::unix::fs::MetadataExt;
+    use std::{env, process};
+
+    const CHUNK: u64 = 64 * 1024;
+    const HOLE: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_file_in_data() {
+        let path = env::temp_dir().join(format!("virt-rs-file-in-data-{}", process::id()));
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        file.write_all(&vec![1; CHUNK as usize]).unwrap();
+        file.seek(SeekFrom::Current(HOLE as i64)).unwrap();
+        file.write_all(&vec![2; CHUNK as usize]).unwrap();
+        // Nothing to check if the filesystem does not support holes.
+        if file.metadata().unwrap().blocks() * 512 >= 2 * CHUNK + HOLE {
+            return;
+        }
+
+        let in_data_at = |mut f: &File, pos: u64| {
+            f.seek(SeekFrom::Start(pos)).unwrap();
+            let ret = file_in_data(f).unwrap();
+            assert_eq!(pos, f.stream_position().unwrap());
+            ret
+        };
+        assert_eq!((true, CHUNK as i64), in_data_at(&file, 0));
+        assert_eq!((false, HOLE as i64), in_data_at(&file, CHUNK));
+        assert_eq!(
+            (false, (HOLE / 2) as i64),
+            in_data_at(&file, CHUNK + HOLE / 2)
+        );
+        assert_eq!((true, CHUNK as i64), in_data_at(&file, CHUNK + HOLE));
+        assert_eq!((false, 0), in_data_at(&file, 2 * CHUNK + HOLE));
+    }
 }
@@ -0,0 +1,317 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <https://www.gnu.org/licenses/>.
+ */
+
+//! Event loop implementation driven by the tokio reactor.
+//!
+//! Requires the `tokio` feature.
+
+use std::collections::HashMap;
+use std::os::unix::io::{FromRawFd, OwnedFd, RawFd};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, watch};
+
+use crate::domain::{Domain, DomainEvent, DomainEventCallback};
+use crate::error::Error;
+use crate::event::{register_event_impl, EventHandleCallback, EventLoop, EventTimeoutCallback};
+
+#[derive(Default)]
+struct TokioState {
+    next_watch: libc::c_int,
+    next_timer: libc::c_int,
+    handles: HashMap<libc::c_int, watch::Sender<sys::virEventHandleType>>,
+    timeouts: HashMap<libc::c_int, watch::Sender<libc::c_int>>,
+}
+
+/// [`EventLoop`] implementation running libvirt handles and timers as
+/// tasks of a tokio runtime.
+///
+/// Once registered, libvirt events are dispatched as long as the
+/// runtime is alive, there is no need to run
+/// [`event_run_default_impl`](crate::event::event_run_default_impl)
+/// from a dedicated thread.
+///
+/// # Examples
+///
+/// ````no_run
+/// use virt::connect::Connect;
+/// use virt::tokio_event::TokioEventLoop;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     TokioEventLoop::register().unwrap();
+///     let conn = Connect::open(Some("test:///default")).unwrap();
+///     // ...
+/// }
+/// ````
+pub struct TokioEventLoop {
+    runtime: Handle,
+    state: Mutex<TokioState>,
+}
+
+impl TokioEventLoop {
+    /// Creates an event loop spawning its tasks on `runtime`.
+    pub fn new(runtime: Handle) -> TokioEventLoop {
+        TokioEventLoop {
+            runtime,
+            state: Mutex::new(TokioState {
+                next_watch: 1,
+                next_timer: 1,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Installs an event loop bound to the current tokio runtime as
+    /// the libvirt event loop implementation.
+    ///
+    /// Like [`register_event_impl`], this fails if an event loop
+    /// implementation was already registered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn register() -> Result<Arc<TokioEventLoop>, Error> {
+        let event_loop = Arc::new(TokioEventLoop::new(Handle::current()));
+        register_event_impl(event_loop.clone())?;
+        Ok(event_loop)
+    }
+
+    fn state(&self) -> MutexGuard<'_, TokioState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+// Returns the events currently pending on fd among the requested
+// ones. tokio readiness is edge-triggered while libvirt expects level
+// triggered notifications, so it is checked again before and after
+// every callback.
+fn pending_events(fd: RawFd, events: sys::virEventHandleType) -> sys::virEventHandleType {
+    let mut pfd = libc::pollfd {
+        fd,
+        events: 0,
+        revents: 0,
+    };
+    if events & sys::VIR_EVENT_HANDLE_READABLE != 0 {
+        pfd.events |= libc::POLLIN;
+    }
+    if events & sys::VIR_EVENT_HANDLE_WRITABLE != 0 {
+        pfd.events |= libc::POLLOUT;
+    }
+    if unsafe { libc::poll(&mut pfd, 1, 0) } <= 0 {
+        return 0;
+    }
+    let mut ret = 0;
+    if pfd.revents & libc::POLLIN != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_READABLE;
+    }
+    if pfd.revents & libc::POLLOUT != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_WRITABLE;
+    }
+    if pfd.revents & (libc::POLLERR | libc::POLLNVAL) != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_ERROR;
+    }
+    if pfd.revents & libc::POLLHUP != 0 {
+        ret |= sys::VIR_EVENT_HANDLE_HANGUP;
+    }
+    ret
+}
+
+async fn run_handle(
+    watch: libc::c_int,
+    fd: RawFd,
+    afd: AsyncFd<OwnedFd>,
+    cb: EventHandleCallback,
+    mut events: watch::Receiver<sys::virEventHandleType>,
+) {
+    loop {
+        let wanted = *events.borrow_and_update();
+        let readable = wanted & sys::VIR_EVENT_HANDLE_READABLE != 0;
+        let writable = wanted & sys::VIR_EVENT_HANDLE_WRITABLE != 0;
+        tokio::select! {
+            changed = events.changed() => {
+                // The handle was removed, the callback is released
+                // when returning.
+                if changed.is_err() {
+                    return;
+                }
+            }
+            guard = afd.readable(), if readable => {
+                let mut guard = match guard {
+                    Ok(guard) => guard,
+                    Err(_) => return,
+                };
+                match pending_events(fd, wanted) {
+                    0 => guard.clear_ready(),
+                    ready => cb.call(watch, fd, ready),
+                }
+            }
+            guard = afd.writable(), if writable => {
+                let mut guard = match guard {
+                    Ok(guard) => guard,
+                    Err(_) => return,
+                };
+                match pending_events(fd, wanted) {
+                    0 => guard.clear_ready(),
+                    ready => cb.call(watch, fd, ready),
+                }
+            }
+        }
+    }
+}
+
+async fn run_timeout(
+    timer: libc::c_int,
+    cb: EventTimeoutCallback,
+    mut frequency: watch::Receiver<libc::c_int>,
+) {
+    loop {
+        let ms = *frequency.borrow_and_update();
+        if ms < 0 {
+            if frequency.changed().await.is_err() {
+                return;
+            }
+            continue;
+        }
+        tokio::select! {
+            changed = frequency.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+            _ = tokio::time::sleep(Duration::from_millis(ms as u64)) => {
+                cb.call(timer);
+                if ms == 0 {
+                    tokio::task::yield_now().await;
+                }
+            }
+        }
+    }
+}
+
+impl EventLoop for TokioEventLoop {
+    fn add_handle(
+        &self,
+        fd: RawFd,
+        events: sys::virEventHandleType,
+        cb: EventHandleCallback,
+    ) -> Result<libc::c_int, EventHandleCallback> {
+        // Register a duplicate of the file descriptor so that the
+        // reactor never sees a descriptor closed and reused by libvirt
+        // while the task is being torn down.
+        let dup = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+        if dup < 0 {
+            return Err(cb);
+        }
+        let owned = unsafe { OwnedFd::from_raw_fd(dup) };
+        let afd = {
+            let _guard = self.runtime.enter();
+            match AsyncFd::with_interest(owned, Interest::READABLE | Interest::WRITABLE) {
+                Ok(afd) => afd,
+                Err(_) => return Err(cb),
+            }
+        };
+
+        let (tx, rx) = watch::channel(events);
+        let watch = {
+            let mut state = self.state();
+            let watch = state.next_watch;
+            state.next_watch += 1;
+            state.handles.insert(watch, tx);
+            watch
+        };
+        self.runtime.spawn(run_handle(watch, fd, afd, cb, rx));
+        Ok(watch)
+    }
+
+    fn update_handle(&self, watch: libc::c_int, events: sys::virEventHandleType) {
+        if let Some(tx) = self.state().handles.get(&watch) {
+            let _ = tx.send(events);
+        }
+    }
+
+    fn remove_handle(&self, watch: libc::c_int) -> bool {
+        // Dropping the sender stops the task, which releases the
+        // callback outside of this call as libvirt requires.
+        let tx = self.state().handles.remove(&watch);
+        tx.is_some()
+    }
+
+    fn add_timeout(
+        &self,
+        timeout: libc::c_int,
+        cb: EventTimeoutCallback,
+    ) -> Result<libc::c_int, EventTimeoutCallback> {
+        let (tx, rx) = watch::channel(timeout);
+        let timer = {
+            let mut state = self.state();
+            let timer = state.next_timer;
+            state.next_timer += 1;
+            state.timeouts.insert(timer, tx);
+            timer
+        };
+        self.runtime.spawn(run_timeout(timer, cb, rx));
+        Ok(timer)
+    }
+
+    fn update_timeout(&self, timer: libc::c_int, timeout: libc::c_int) {
+        if let Some(tx) = self.state().timeouts.get(&timer) {
+            let _ = tx.send(timeout);
+        }
+    }
+
+    fn remove_timeout(&self, timer: libc::c_int) -> bool {
+        let tx = self.state().timeouts.remove(&timer);
+        tx.is_some()
+    }
+}
+
+/// Domain events delivered through a channel, see
+/// [`crate::connect::Connect::domain_event_receiver`].
+///
+/// The underlying callback is deregistered when the receiver is
+/// dropped.
+pub struct DomainEventReceiver {
+    callback: DomainEventCallback,
+    rx: mpsc::UnboundedReceiver<(Domain, DomainEvent)>,
+}
+
+impl DomainEventReceiver {
+    pub(crate) fn new(
+        callback: DomainEventCallback,
+        rx: mpsc::UnboundedReceiver<(Domain, DomainEvent)>,
+    ) -> DomainEventReceiver {
+        DomainEventReceiver { callback, rx }
+    }
+
+    /// Returns the libvirt callback id.
+    pub fn id(&self) -> i32 {
+        self.callback.id()
+    }
+
+    /// Waits for the next event. Returns `None` once libvirt released
+    /// the callback, e.g. because the connection was closed.
+    pub async fn recv(&mut self) -> Option<(Domain, DomainEvent)> {
+        self.rx.recv().await
+    }
+}
@@ -8,7 +8,6 @@ pub enum ParamIn<'a> {
     UInt64(&'a mut Option<u64>),
     #[allow(dead_code)]
     Float64(&'a mut Option<f64>),
-    #[allow(dead_code)]
     Bool(&'a mut Option<bool>),
     String(&'a mut Option<String>),
     #[allow(dead_code)]
@@ -22,7 +21,6 @@ pub enum ParamOut<'a> {
     UInt64(&'a Option<u64>),
     #[allow(dead_code)]
     Float64(&'a Option<f64>),
-    #[allow(dead_code)]
     Bool(&'a Option<bool>),
     String(&'a Option<String>),
     VecString(&'a Vec<String>),
@@ -198,6 +196,91 @@ pub fn to_params(mut fields: Vec<FieldOut>) -> Vec<sys::virTypedParameter> {
     params
 }
 
+/// Value of a typed parameter whose name is not known in advance.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypedParamValue {
+    Int32(i32),
+    UInt32(u32),
+    Int64(i64),
+    UInt64(u64),
+    Float64(f64),
+    Bool(bool),
+    String(String),
+}
+
+impl TypedParamValue {
+    /// Returns the value of an integer parameter that fits in a `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            TypedParamValue::Int32(v) => u64::try_from(v).ok(),
+            TypedParamValue::UInt32(v) => Some(u64::from(v)),
+            TypedParamValue::Int64(v) => u64::try_from(v).ok(),
+            TypedParamValue::UInt64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer parameter that fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            TypedParamValue::Int32(v) => Some(i64::from(v)),
+            TypedParamValue::UInt32(v) => Some(i64::from(v)),
+            TypedParamValue::Int64(v) => Some(v),
+            TypedParamValue::UInt64(v) => i64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a floating point parameter.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            TypedParamValue::Float64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a boolean parameter.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            TypedParamValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a string parameter.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TypedParamValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Converts every parameter into a `(name, value)` pair, skipping
+/// parameters of unknown type.
+pub fn from_params_generic(params: &[sys::virTypedParameter]) -> Vec<(String, TypedParamValue)> {
+    let mut ret = Vec::with_capacity(params.len());
+    for param in params.iter() {
+        let name = unsafe { c_chars_to_string!(param.field.as_ptr(), nofree) };
+        let value = unsafe {
+            match param.type_ as u32 {
+                sys::VIR_TYPED_PARAM_INT => TypedParamValue::Int32(param.value.i),
+                sys::VIR_TYPED_PARAM_UINT => TypedParamValue::UInt32(param.value.ui),
+                sys::VIR_TYPED_PARAM_LLONG => TypedParamValue::Int64(param.value.l),
+                sys::VIR_TYPED_PARAM_ULLONG => TypedParamValue::UInt64(param.value.ul),
+                sys::VIR_TYPED_PARAM_DOUBLE => TypedParamValue::Float64(param.value.d),
+                sys::VIR_TYPED_PARAM_BOOLEAN => TypedParamValue::Bool(param.value.b != 0),
+                sys::VIR_TYPED_PARAM_STRING => {
+                    TypedParamValue::String(c_chars_to_string!(param.value.s, nofree))
+                }
+                _ => continue,
+            }
+        };
+        ret.push((name, value));
+    }
+    ret
+}
+
 fn to_arr(name: &str) -> [libc::c_char; 80] {
     let mut field: [libc::c_char; 80] = [0; 80];
     for (a, c) in field.iter_mut().zip(name.as_bytes()) {
@@ -209,7 +292,7 @@ fn to_arr(name: &str) -> [libc::c_char; 80] {
 #[cfg(test)]
 mod test {
 
-    use crate::typedparams::{from_params, to_params};
+    use crate::typedparams::{from_params, from_params_generic, to_params, TypedParamValue};
 
     #[derive(PartialEq, Debug)]
     struct Demo {
@@ -282,4 +365,31 @@ mod test {
         };
         roundtrip(demo);
     }
+
+    #[test]
+    fn test_from_params_generic() {
+        let demo: Demo = Demo {
+            vi32: Some(-1729),
+            vu32: None,
+            vi64: None,
+            vu64: Some(87539319),
+            vf64: None,
+            vbool: Some(false),
+            vstring: Some("taxicab".to_string()),
+        };
+        let params = to_params(fields!(param_field_out, demo));
+        let values = from_params_generic(&params);
+        assert_eq!(
+            values,
+            vec![
+                ("int32".to_string(), TypedParamValue::Int32(-1729)),
+                ("uint64".to_string(), TypedParamValue::UInt64(87539319)),
+                ("bool".to_string(), TypedParamValue::Bool(false)),
+                (
+                    "string".to_string(),
+                    TypedParamValue::String("taxicab".to_string())
+                ),
+            ]
+        );
+    }
 }
@@ -20,9 +20,12 @@
 // ignored tests are executed.
 #![allow(dead_code)]
 
+use std::ptr;
+
 use virt::connect::Connect;
 use virt::domain::Domain;
 use virt::error::Error;
+use virt::event;
 use virt::interface::Interface;
 use virt::network::Network;
 use virt::storage_pool::StoragePool;
@@ -36,6 +39,21 @@ pub fn qemu_conn() -> Connect {
     Connect::open(Some("qemu:///system")).unwrap()
 }
 
+/// Runs the default event loop until `done` returns true, failing the
+/// test when it still does not after 500 iterations. A 10ms timer
+/// keeps each iteration from blocking when no event shows up.
+pub fn run_event_loop_until(done: impl Fn() -> bool) {
+    let tick = event::event_add_timeout(10, |_, _| {}, ptr::null_mut()).unwrap();
+    for _ in 0..500 {
+        if done() {
+            break;
+        }
+        event::event_run_default_impl().unwrap();
+    }
+    assert_eq!(Ok(()), tick.event_remove_timeout());
+    assert!(done(), "event loop gave up waiting for events");
+}
+
 pub fn close(conn: Connect) {
     drop(conn);
 }
@@ -18,7 +18,12 @@
 
 mod common;
 
+use std::sync::Arc;
+
 use virt::connect::Connect;
+use virt::domain::{DomainState, DomainStatsFlags, DomainStatsTypes};
+use virt::event;
+use virt::nodedev::NodeDeviceEventId;
 
 #[test]
 fn test_version() {
@@ -123,6 +128,27 @@ fn test_domain_capabilities() {
     common::close(c);
 }
 
+#[test]
+fn test_all_domain_stats() {
+    let c = common::conn();
+    let stats = c
+        .all_domain_stats(
+            DomainStatsTypes::STATE | DomainStatsTypes::VCPU,
+            DomainStatsFlags::empty(),
+        )
+        .unwrap();
+    let record = stats
+        .iter()
+        .find(|r| r.domain.name().unwrap() == "test")
+        .expect("test domain should be reported");
+    assert_eq!(
+        Some(DomainState::Running),
+        record.state.and_then(|(state, _)| state.known())
+    );
+    assert_eq!(record.vcpu_current, Some(record.vcpus.len() as u32));
+    common::close(c);
+}
+
 #[test]
 fn test_get_node_info() {
     let c = common::conn();
@@ -234,3 +260,43 @@ fn test_get_cells_free_memory() {
     assert!(free[1] == 4194304, "Invalid free pages for NUMA node 1");
     common::close(c);
 }
+
+#[test]
+fn test_node_device_event_register_any() {
+    event::event_register_default_impl().unwrap();
+    let c = common::conn();
+    let lifecycle = c
+        .node_device_event_register_any(None, NodeDeviceEventId::Lifecycle, |_, _, _| {})
+        .unwrap();
+    let update = c
+        .node_device_event_register_any(None, NodeDeviceEventId::Update, |_, _, _| {})
+        .unwrap();
+    assert_ne!(lifecycle.id(), update.id());
+    assert_eq!(Ok(()), lifecycle.deregister());
+    drop(update);
+    common::close(c);
+}
+
+#[test]
+fn test_close_callback() {
+    let mut c = common::conn();
+    let data = Arc::new(());
+    let held = Arc::clone(&data);
+    let res = c.register_close_callback(move |_, _| {
+        let _ = &held;
+    });
+    assert_eq!(Ok(()), res);
+    assert_eq!(2, Arc::strong_count(&data));
+    assert_eq!(Ok(()), c.unregister_close_callback());
+    assert_eq!(1, Arc::strong_count(&data));
+
+    let held = Arc::clone(&data);
+    let res = c.register_close_callback(move |_, _| {
+        let _ = &held;
+    });
+    assert_eq!(Ok(()), res);
+    assert_eq!(2, Arc::strong_count(&data));
+    // Dropping the connection must unregister the callback.
+    common::close(c);
+    assert_eq!(1, Arc::strong_count(&data));
+}
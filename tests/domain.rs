@@ -18,13 +18,22 @@
 
 mod common;
 
+use std::sync::{Arc, Mutex};
+
 use uuid::Uuid;
 
 use virt::domain::{
-    Domain, DomainPausedReason, DomainRunningReason, DomainShutoffReason, DomainState,
-    DomainStateReason, MemoryParameters, NUMAParameters, SchedulerInfo,
+    BlkioParameters, BlockIoTuneParameters, CpuMap, Domain, DomainEvent, DomainEventId,
+    DomainEventType, DomainEventTypeEnum, DomainPausedReason, DomainRunningReason,
+    DomainShutoffReason, DomainState, DomainStateReason, DomainStatsFlags, DomainStatsTypes,
+    InterfaceParameters, MemoryParameters, NUMAParameters, PerfEvents, SchedulerInfo,
+};
+use virt::domain_checkpoint::{
+    DomainCheckpoint, DomainCheckpointCreateFlags, DomainCheckpointDeleteFlags,
+    DomainCheckpointListFlags,
 };
 use virt::error::ErrorNumber;
+use virt::event;
 use virt::sys;
 
 fn tdom(exec_test: fn(dom: Domain)) {
@@ -191,6 +200,194 @@ fn test_numa_params() {
     tdom(t);
 }
 
+#[test]
+fn test_block_io_tune() {
+    fn t(dom: Domain) {
+        let newinfo = BlockIoTuneParameters {
+            total_iops_sec: Some(1000),
+            group_name: Some("noisy".to_string()),
+            ..Default::default()
+        };
+        dom.set_block_io_tune("vda", newinfo, 0).unwrap();
+
+        let info = dom.block_io_tune("vda", 0).unwrap();
+        assert_eq!(info.total_iops_sec, Some(1000));
+        assert_eq!(info.read_iops_sec, Some(0));
+        assert_eq!(info.group_name, Some("noisy".to_string()));
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_blkio_parameters() {
+    fn t(dom: Domain) {
+        let params = BlkioParameters {
+            weight: Some(500),
+            ..Default::default()
+        };
+        dom.set_blkio_parameters(params, 0).unwrap();
+
+        let params = dom.blkio_parameters(0).unwrap();
+        assert_eq!(params.weight, Some(500));
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_interface_parameters() {
+    fn t(dom: Domain) {
+        let params = InterfaceParameters {
+            inbound_average: Some(1000),
+            inbound_peak: Some(2000),
+            outbound_average: Some(500),
+            ..Default::default()
+        };
+        dom.set_interface_parameters("aa:bb:cc:dd:ee:ff", params, 0)
+            .unwrap();
+
+        let params = dom.interface_parameters("aa:bb:cc:dd:ee:ff", 0).unwrap();
+        assert_eq!(params.inbound_average, Some(1000));
+        assert_eq!(params.inbound_peak, Some(2000));
+        assert_eq!(params.outbound_average, Some(500));
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_perf_events() {
+    fn t(dom: Domain) {
+        let events = PerfEvents {
+            cpu_cycles: Some(true),
+            ..Default::default()
+        };
+        dom.set_perf_events(events, 0).unwrap();
+
+        let events = dom.perf_events(0).unwrap();
+        assert_eq!(events.cpu_cycles, Some(true));
+        assert_eq!(events.cache_misses, Some(false));
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_cpu_map() {
+    let map: CpuMap = "0-3,8".parse().unwrap();
+    assert_eq!(&[0x0f, 0x01], map.as_bytes());
+    assert_eq!(vec![0, 1, 2, 3, 8], map.iter().collect::<Vec<u32>>());
+    assert_eq!("0-3,8", map.to_string());
+    assert!(!map.is_empty());
+    assert!(CpuMap::new(16).is_empty());
+    assert!("0,^0".parse::<CpuMap>().unwrap().is_empty());
+    assert!("3-1".parse::<CpuMap>().is_err());
+    assert!("a".parse::<CpuMap>().is_err());
+    assert_eq!(
+        vec![6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17],
+        "6-17"
+            .parse::<CpuMap>()
+            .unwrap()
+            .iter()
+            .collect::<Vec<u32>>()
+    );
+    assert_eq!(&[0x10], "4-4".parse::<CpuMap>().unwrap().as_bytes());
+    let err = "0-4294967295".parse::<CpuMap>().unwrap_err();
+    assert!(err.code().is(ErrorNumber::InvalidArg));
+    assert!("4294967295".parse::<CpuMap>().is_err());
+    assert!("0-4095".parse::<CpuMap>().is_ok());
+}
+
+#[test]
+fn test_vcpus() {
+    fn t(dom: Domain) {
+        let info = dom.info().unwrap();
+        let vcpus = dom.vcpus().unwrap();
+        assert_eq!(info.nr_virt_cpu as usize, vcpus.len());
+        for (i, vcpu) in vcpus.iter().enumerate() {
+            assert_eq!(i as u32, vcpu.number);
+            assert!(vcpu.affinity.iter().next().is_some());
+        }
+
+        let pins = dom.vcpu_pin_info(0).unwrap();
+        assert_eq!(vcpus.len(), pins.len());
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_guest_info() {
+    fn t(dom: Domain) {
+        let info = dom
+            .guest_info(
+                sys::VIR_DOMAIN_GUEST_INFO_HOSTNAME | sys::VIR_DOMAIN_GUEST_INFO_FILESYSTEM,
+                0,
+            )
+            .unwrap();
+        assert_eq!(dom.hostname(0).ok(), info.hostname);
+        assert!(info.users.is_empty());
+        assert!(info.os.is_none());
+
+        let fsinfo = dom.get_fs_info(0).unwrap();
+        assert!(!fsinfo.is_empty());
+        assert_eq!(fsinfo.len(), info.filesystems.len());
+        for (want, fs) in fsinfo.iter().zip(&info.filesystems) {
+            assert_eq!(Some(&want.mountpoint), fs.mountpoint.as_ref());
+            assert_eq!(Some(&want.name), fs.name.as_ref());
+            assert_eq!(Some(&want.fstype), fs.fstype.as_ref());
+            let aliases: Vec<String> = fs.disks.iter().filter_map(|d| d.alias.clone()).collect();
+            assert_eq!(want.dev_aliases, aliases);
+        }
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_fs_freeze() {
+    fn t(dom: Domain) {
+        let fsinfo = dom.get_fs_info(0).unwrap();
+        assert!(fsinfo.iter().any(|fs| fs.mountpoint == "/"));
+
+        let frozen = dom.fs_freeze(&["/"], 0).unwrap();
+        assert_eq!(1, frozen.frozen());
+        assert_eq!(Ok(1), frozen.thaw());
+
+        // Thawed on drop, so freezing again must work
+        drop(dom.fs_freeze(&["/"], 0).unwrap());
+        let frozen = dom.fs_freeze(&["/"], 0).unwrap();
+        assert_eq!(1, frozen.frozen());
+        assert_eq!(Ok(1), frozen.thaw());
+
+        // The guard thaws everything, not only what it froze
+        let frozen = dom.fs_freeze(&[], 0).unwrap();
+        assert_eq!(2, frozen.frozen());
+        assert_eq!(Ok(2), frozen.thaw());
+        let frozen = dom.fs_freeze(&["/"], 0).unwrap();
+        assert_eq!(1, dom.fs_freeze(&["/boot"], 0).unwrap().frozen());
+        assert_eq!(Ok(0), frozen.thaw());
+        assert_eq!(Ok(0), dom.fs_thaw(&[], 0));
+    }
+    tdom(t);
+}
+
+#[test]
+fn test_guest_vcpus() {
+    fn t(dom: Domain) {
+        let empty = CpuMap::default();
+        let err = dom.set_guest_vcpus(&empty, true, 0).unwrap_err();
+        assert!(err.code().is(ErrorNumber::InvalidArg));
+        let err = dom.set_vcpu(&empty, true, 0).unwrap_err();
+        assert!(err.code().is(ErrorNumber::InvalidArg));
+
+        match dom.guest_vcpus(0) {
+            Ok(info) => {
+                for cpu in info.online.iter() {
+                    assert!(info.vcpus.is_set(cpu), "Online vCPU {cpu} is unknown");
+                }
+            }
+            Err(e) => assert!(e.code().is(ErrorNumber::NoSupport), "Unexpected error: {e}"),
+        }
+    }
+    tdom(t);
+}
+
 #[test]
 fn test_lookup_domain_by_id() {
     let c = common::conn();
@@ -214,6 +411,25 @@ fn test_lookup_domain_by_name() {
     common::close(c);
 }
 
+#[test]
+fn test_iothreads() {
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "iothreads", false);
+    let ids = |d: &Domain| -> Vec<u32> {
+        d.iothread_info(sys::VIR_DOMAIN_AFFECT_CONFIG)
+            .unwrap()
+            .iter()
+            .map(|t| t.iothread_id)
+            .collect()
+    };
+    d.add_iothread(3, sys::VIR_DOMAIN_AFFECT_CONFIG).unwrap();
+    assert!(ids(&d).contains(&3));
+    d.del_iothread(3, sys::VIR_DOMAIN_AFFECT_CONFIG).unwrap();
+    assert!(!ids(&d).contains(&3));
+    common::clean_dom(d);
+    common::close(c);
+}
+
 #[test]
 fn test_create_with_flags() {
     let c = common::conn();
@@ -445,3 +661,120 @@ fn test_get_cpu_stats() {
     let stats = d.cpu_stats(-1, 1, 0).unwrap();
     assert!(!stats.is_empty(), "Test driver should return a stat.");
 }
+
+#[test]
+fn test_list_get_stats() {
+    let c = common::conn();
+    let d = c.lookup_domain_by_name("test").unwrap();
+    let stats =
+        Domain::list_get_stats(&[d], DomainStatsTypes::STATE, DomainStatsFlags::empty()).unwrap();
+    assert_eq!(1, stats.len());
+    assert_eq!(Ok(String::from("test")), stats[0].domain.name());
+    assert_eq!(
+        Some(DomainState::Running),
+        stats[0].state.and_then(|(state, _)| state.known())
+    );
+    common::close(c);
+}
+
+#[test]
+fn test_checkpoints() {
+    let c = common::conn();
+    let xml = "<domain type='test'>
+                 <name>libvirt-rs-test-checkpoints</name>
+                 <memory unit='KiB'>128</memory>
+                 <os>
+                   <type>hvm</type>
+                 </os>
+                 <devices>
+                   <disk type='file' device='disk'>
+                     <driver name='qemu' type='qcow2'/>
+                     <source file='/guest/checkpoints.qcow2'/>
+                     <target dev='vda' bus='virtio'/>
+                   </disk>
+                 </devices>
+               </domain>";
+    let d = c.create_domain_xml(xml, 0).unwrap();
+    let names = |list: Vec<DomainCheckpoint>| {
+        let mut names: Vec<String> = list.iter().map(|cp| cp.name().unwrap()).collect();
+        names.sort();
+        names
+    };
+
+    let c1 = d
+        .checkpoint_create_xml(
+            "<domaincheckpoint><name>c1</name></domaincheckpoint>",
+            DomainCheckpointCreateFlags::empty(),
+        )
+        .unwrap();
+    d.checkpoint_create_xml(
+        "<domaincheckpoint><name>c2</name></domaincheckpoint>",
+        DomainCheckpointCreateFlags::empty(),
+    )
+    .unwrap();
+
+    let c2 = d.checkpoint_lookup_by_name("c2", 0).unwrap();
+    assert_eq!(Ok(String::from("c2")), c2.name());
+    assert_eq!(Ok(String::from("c1")), c2.parent(0).and_then(|p| p.name()));
+    assert!(c1.parent(0).is_err());
+    assert_eq!(
+        vec!["c1", "c2"],
+        names(
+            d.list_all_checkpoints(DomainCheckpointListFlags::empty())
+                .unwrap()
+        )
+    );
+    assert_eq!(
+        vec!["c1"],
+        names(
+            d.list_all_checkpoints(DomainCheckpointListFlags::ROOTS)
+                .unwrap()
+        )
+    );
+    assert_eq!(
+        vec!["c2"],
+        names(
+            c1.list_all_children(DomainCheckpointListFlags::empty())
+                .unwrap()
+        )
+    );
+
+    assert_eq!(Ok(()), c1.delete(DomainCheckpointDeleteFlags::CHILDREN));
+    assert!(d
+        .list_all_checkpoints(DomainCheckpointListFlags::empty())
+        .unwrap()
+        .is_empty());
+    assert!(d.checkpoint_lookup_by_name("c2", 0).is_err());
+
+    common::clean_dom(d);
+    common::close(c);
+}
+
+#[test]
+fn test_domain_event_lifecycle() {
+    event::event_register_default_impl().unwrap();
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "event_lifecycle", false);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let cb = c
+        .domain_event_register_any(Some(&d), DomainEventId::Lifecycle, move |_, _, ev| {
+            if let DomainEvent::Lifecycle { event, .. } = ev {
+                seen.lock().unwrap().push(event);
+            }
+        })
+        .unwrap();
+    assert_eq!(Ok(()), d.create_with_flags(0));
+    assert_eq!(Ok(()), d.suspend());
+    common::run_event_loop_until(|| events.lock().unwrap().len() >= 2);
+    assert_eq!(
+        vec![
+            DomainEventTypeEnum::from(DomainEventType::Started),
+            DomainEventTypeEnum::from(DomainEventType::Suspended),
+        ],
+        *events.lock().unwrap()
+    );
+    assert_eq!(Ok(()), cb.deregister());
+    common::clean_dom(d);
+    common::close(c);
+}
@@ -0,0 +1,111 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <https://www.gnu.org/licenses/>.
+ *
+ * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
+ */
+
+mod common;
+
+use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use virt::domain::{DomainEvent, DomainEventId, DomainEventType};
+use virt::event::{self, PollEventLoop};
+use virt::sys;
+
+fn run_until(event_loop: &PollEventLoop, cond: impl Fn() -> bool) {
+    while !cond() {
+        event_loop.run_once().unwrap();
+    }
+}
+
+fn check_handle(event_loop: &PollEventLoop) {
+    let mut fds: [RawFd; 2] = [-1; 2];
+    assert_eq!(0, unsafe { libc::pipe(fds.as_mut_ptr()) });
+    let fired = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&fired);
+    let watch = event::event_add_handle(
+        fds[0],
+        sys::VIR_EVENT_HANDLE_READABLE,
+        move |_, _, events, _| {
+            if events & sys::VIR_EVENT_HANDLE_READABLE != 0 {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }
+        },
+        std::ptr::null_mut(),
+    )
+    .unwrap();
+    assert_eq!(1, unsafe {
+        libc::write(fds[1], b"x".as_ptr() as *const _, 1)
+    });
+    run_until(event_loop, || fired.load(Ordering::SeqCst) > 0);
+    assert_eq!(Ok(()), watch.event_remove_handle());
+    event_loop.run_once().unwrap();
+    unsafe {
+        libc::close(fds[0]);
+        libc::close(fds[1]);
+    }
+}
+
+fn check_timeout(event_loop: &PollEventLoop) {
+    let fired = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&fired);
+    let timer = event::event_add_timeout(
+        10,
+        move |_, _| {
+            seen.fetch_add(1, Ordering::SeqCst);
+        },
+        std::ptr::null_mut(),
+    )
+    .unwrap();
+    run_until(event_loop, || fired.load(Ordering::SeqCst) >= 2);
+    assert_eq!(Ok(()), timer.event_remove_timeout());
+}
+
+fn check_domain_events(event_loop: &PollEventLoop) {
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "poll_event_loop", false);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let cb = c
+        .domain_event_register_any(Some(&d), DomainEventId::Lifecycle, move |_, _, ev| {
+            if let DomainEvent::Lifecycle { event, .. } = ev {
+                seen.lock().unwrap().push(event);
+            }
+        })
+        .unwrap();
+    assert_eq!(Ok(()), d.create_with_flags(0));
+    assert_eq!(Ok(()), d.destroy());
+    run_until(event_loop, || events.lock().unwrap().len() >= 2);
+    let events = events.lock().unwrap();
+    assert!(events[0].is(DomainEventType::Started));
+    assert!(events[1].is(DomainEventType::Stopped));
+    assert_eq!(Ok(()), cb.deregister());
+    common::clean_dom(d);
+    common::close(c);
+}
+
+// The event loop implementation is process wide, so every scenario
+// runs from a single test to keep one thread driving the loop.
+#[test]
+fn test_poll_event_loop() {
+    let event_loop = Arc::new(PollEventLoop::new().unwrap());
+    event::register_event_impl(event_loop.clone()).unwrap();
+    assert!(event::register_event_impl(event_loop.clone()).is_err());
+    check_handle(&event_loop);
+    check_timeout(&event_loop);
+    check_domain_events(&event_loop);
+}
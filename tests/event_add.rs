@@ -0,0 +1,95 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <https://www.gnu.org/licenses/>.
+ */
+
+use std::os::unix::io::RawFd;
+use std::ptr;
+use std::sync::Arc;
+
+use virt::event::{self, EventHandleCallback, EventLoop, EventTimeoutCallback};
+use virt::sys;
+
+struct RejectingEventLoop;
+
+impl EventLoop for RejectingEventLoop {
+    fn add_handle(
+        &self,
+        _fd: RawFd,
+        _events: sys::virEventHandleType,
+        cb: EventHandleCallback,
+    ) -> Result<libc::c_int, EventHandleCallback> {
+        Err(cb)
+    }
+
+    fn update_handle(&self, _watch: libc::c_int, _events: sys::virEventHandleType) {}
+
+    fn remove_handle(&self, _watch: libc::c_int) -> bool {
+        false
+    }
+
+    fn add_timeout(
+        &self,
+        _timeout: libc::c_int,
+        cb: EventTimeoutCallback,
+    ) -> Result<libc::c_int, EventTimeoutCallback> {
+        Err(cb)
+    }
+
+    fn update_timeout(&self, _timer: libc::c_int, _timeout: libc::c_int) {}
+
+    fn remove_timeout(&self, _timer: libc::c_int) -> bool {
+        false
+    }
+}
+
+// Adding a handle and a timer must fail and release the callback
+// data exactly once.
+fn check_add_failure() {
+    let data = Arc::new(());
+
+    let held = Arc::clone(&data);
+    let res = event::event_add_handle(
+        0,
+        sys::VIR_EVENT_HANDLE_READABLE,
+        move |_, _, _, _| {
+            let _ = &held;
+        },
+        ptr::null_mut(),
+    );
+    assert!(res.is_err());
+    assert_eq!(1, Arc::strong_count(&data));
+
+    let held = Arc::clone(&data);
+    let res = event::event_add_timeout(
+        0,
+        move |_, _| {
+            let _ = &held;
+        },
+        ptr::null_mut(),
+    );
+    assert!(res.is_err());
+    assert_eq!(1, Arc::strong_count(&data));
+}
+
+// The event loop implementation is process wide, so both scenarios
+// run from a single test: first without any implementation, where
+// libvirt itself refuses the handle and timer, then with one that
+// rejects them.
+#[test]
+fn test_event_add_failure() {
+    check_add_failure();
+    event::register_event_impl(Arc::new(RejectingEventLoop)).unwrap();
+    check_add_failure();
+}
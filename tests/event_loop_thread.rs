@@ -0,0 +1,51 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <https://www.gnu.org/licenses/>.
+ */
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use virt::domain::DomainEventId;
+use virt::event::EventLoopThread;
+
+#[test]
+fn test_event_loop_thread() {
+    let event_loop = EventLoopThread::start().unwrap();
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "event_loop_thread", false);
+    let fired = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&fired);
+    let cb = c
+        .domain_event_register_any(Some(&d), DomainEventId::Lifecycle, move |_, _, _| {
+            seen.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    assert_eq!(Ok(()), d.create_with_flags(0));
+    for _ in 0..500 {
+        if fired.load(Ordering::SeqCst) > 0 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(fired.load(Ordering::SeqCst) > 0);
+    assert_eq!(Ok(()), cb.deregister());
+    common::clean_dom(d);
+    common::close(c);
+    assert_eq!(Ok(()), event_loop.stop());
+}
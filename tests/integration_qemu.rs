@@ -21,8 +21,22 @@
 
 mod common;
 
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::MetadataExt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{env, process, thread};
+
 use virt::connect::{Connect, ConnectAuth, ConnectCredential, ConnectCredentialType};
-use virt::domain::{DomainRunningReason, DomainState, DomainStateReason};
+use virt::domain::{
+    BackupBeginFlags, BlockCopyFlags, BlockCopyParameters, BlockJobAbortFlags, BlockJobInfoFlags,
+    BlockJobSetSpeedFlags, DomainBlockJobType, DomainRunningReason, DomainState, DomainStateReason,
+};
+use virt::event;
+use virt::secret::{SecretEvent, SecretEventId, SecretEventLifecycleType};
+use virt::storage_vol::StorageVol;
+use virt::stream::Stream;
 use virt::sys;
 
 #[test]
@@ -187,3 +201,260 @@ fn test_domain_memory_stats() {
     common::clean_dom(d);
     common::close(c);
 }
+
+#[test]
+#[ignore]
+fn test_block_copy_job() {
+    let c = common::qemu_conn();
+    let p = common::build_storage_pool(&c, "blockjob", false);
+    assert_eq!(Ok(()), p.create(0));
+    let src = common::build_storage_vol(&p, "blockjob-src.img", 1024);
+    let dst = common::build_storage_vol(&p, "blockjob-dst.img", 1024);
+    let xml = format!(
+        "<domain type='qemu'>
+           <name>libvirt-rs-test-blockjob</name>
+           <memory unit='MiB'>128</memory>
+           <os>
+             <type>hvm</type>
+           </os>
+           <devices>
+             <disk type='file' device='disk'>
+               <driver name='qemu' type='raw'/>
+               <source file='{}'/>
+               <target dev='vda' bus='virtio'/>
+             </disk>
+           </devices>
+         </domain>",
+        src.path().unwrap()
+    );
+    let d = c.create_domain_xml(&xml, 0).unwrap();
+    assert!(d
+        .block_job_info("vda", BlockJobInfoFlags::empty())
+        .unwrap()
+        .is_none());
+
+    let dest_xml = format!(
+        "<disk type='file'>
+           <driver type='raw'/>
+           <source file='{}'/>
+         </disk>",
+        dst.path().unwrap()
+    );
+    assert_eq!(
+        Ok(()),
+        d.block_copy(
+            "vda",
+            &dest_xml,
+            BlockCopyParameters::default(),
+            BlockCopyFlags::REUSE_EXT
+        )
+    );
+    assert_eq!(
+        Ok(()),
+        d.block_job_set_speed("vda", 1, BlockJobSetSpeedFlags::empty())
+    );
+    loop {
+        let info = d
+            .block_job_info("vda", BlockJobInfoFlags::empty())
+            .unwrap()
+            .expect("the copy job should be running");
+        assert!(info.r#type.is(DomainBlockJobType::Copy));
+        assert_eq!(1, info.bandwidth);
+        if info.end != 0 && info.cur == info.end {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert_eq!(
+        Ok(()),
+        d.block_job_abort("vda", BlockJobAbortFlags::empty())
+    );
+    assert!(d
+        .block_job_info("vda", BlockJobInfoFlags::empty())
+        .unwrap()
+        .is_none());
+
+    common::clean_dom(d);
+    common::clean_vol(src);
+    common::clean_vol(dst);
+    common::clean_pool(p);
+    common::close(c);
+}
+
+#[test]
+#[ignore]
+fn test_backup_job() {
+    let c = common::qemu_conn();
+    let p = common::build_storage_pool(&c, "backup", false);
+    assert_eq!(Ok(()), p.create(0));
+    // Large enough for the job to still run when its XML is queried.
+    let src = common::build_storage_vol(&p, "backup-src.img", 131072);
+    let dst = common::build_storage_vol(&p, "backup-dst.img", 131072);
+    let xml = format!(
+        "<domain type='qemu'>
+           <name>libvirt-rs-test-backup</name>
+           <memory unit='MiB'>128</memory>
+           <os>
+             <type>hvm</type>
+           </os>
+           <devices>
+             <disk type='file' device='disk'>
+               <driver name='qemu' type='raw'/>
+               <source file='{}'/>
+               <target dev='vda' bus='virtio'/>
+             </disk>
+           </devices>
+         </domain>",
+        src.path().unwrap()
+    );
+    let d = c.create_domain_xml(&xml, 0).unwrap();
+
+    let dst_path = dst.path().unwrap();
+    let backup_xml = format!(
+        "<domainbackup mode='push'>
+           <disks>
+             <disk name='vda' type='file'>
+               <driver type='raw'/>
+               <target file='{dst_path}'/>
+             </disk>
+           </disks>
+         </domainbackup>"
+    );
+    assert_eq!(
+        Ok(()),
+        d.backup_begin(&backup_xml, None, BackupBeginFlags::REUSE_EXTERNAL)
+    );
+    let desc = d.backup_xml_desc(0).unwrap();
+    assert!(desc.contains(&dst_path));
+    for _ in 0..500 {
+        if d.job_stats(0).unwrap().r#type == sys::VIR_DOMAIN_JOB_NONE as i32 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert_eq!(
+        sys::VIR_DOMAIN_JOB_NONE as i32,
+        d.job_stats(0).unwrap().r#type
+    );
+    let stats = d.job_stats(sys::VIR_DOMAIN_JOB_STATS_COMPLETED).unwrap();
+    assert_eq!(
+        Some(sys::VIR_DOMAIN_JOB_OPERATION_BACKUP as i32),
+        stats.operation
+    );
+    assert_eq!(Some(true), stats.success);
+
+    common::clean_dom(d);
+    common::clean_vol(src);
+    common::clean_vol(dst);
+    common::clean_pool(p);
+    common::close(c);
+}
+
+// Secrets are handled by the secret driver of the daemon, the test
+// driver has no support for them.
+#[test]
+#[ignore]
+fn test_secret_event_lifecycle() {
+    const UUID: &str = "6a4b5a3e-0d0c-4a8f-9a4e-3c1d2b7e8f90";
+    event::event_register_default_impl().unwrap();
+    let c = common::qemu_conn();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let cb = c
+        .secret_event_register_any(None, SecretEventId::Lifecycle, move |_, s, ev| {
+            if s.uuid_string().as_deref() != Ok(UUID) {
+                return;
+            }
+            if let SecretEvent::Lifecycle { event, .. } = ev {
+                seen.lock().unwrap().push(event);
+            }
+        })
+        .unwrap();
+    let xml = format!(
+        "<secret ephemeral='yes' private='yes'>
+           <uuid>{UUID}</uuid>
+           <usage type='volume'>
+             <volume>/var/lib/libvirt/images/libvirt-rs-test-secret.img</volume>
+           </usage>
+         </secret>"
+    );
+    let s = c.define_secret_xml(&xml, 0).unwrap();
+    assert_eq!(Ok(()), s.undefine());
+    common::run_event_loop_until(|| events.lock().unwrap().len() >= 2);
+    let events = events.lock().unwrap();
+    assert!(events[0].is(SecretEventLifecycleType::Defined));
+    assert!(events[1].is(SecretEventLifecycleType::Undefined));
+    assert_eq!(Ok(()), cb.deregister());
+    common::close(c);
+}
+
+fn temp_file(name: &str) -> File {
+    let path = env::temp_dir().join(format!("libvirt-rs-test-{name}-{}", process::id()));
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+    file
+}
+
+#[test]
+#[ignore]
+fn test_sparse_stream_round_trip() {
+    const CHUNK: u64 = 64 * 1024;
+    const HOLE: u64 = 1024 * 1024;
+    const SIZE: u64 = 2 * CHUNK + HOLE;
+
+    // data, hole, data
+    let mut src = temp_file("sparse-src");
+    src.write_all(&vec![1; CHUNK as usize]).unwrap();
+    src.seek(SeekFrom::Current(HOLE as i64)).unwrap();
+    src.write_all(&vec![2; CHUNK as usize]).unwrap();
+    src.seek(SeekFrom::Start(0)).unwrap();
+
+    let c = common::qemu_conn();
+    let p = common::build_storage_pool(&c, "sparse", false);
+    assert_eq!(Ok(()), p.create(0));
+    let xml = format!(
+        "<volume type='file'>
+           <name>sparse</name>
+           <allocation>0</allocation>
+           <capacity>{SIZE}</capacity>
+         </volume>"
+    );
+    let v = StorageVol::create_xml(&p, &xml, 0).unwrap();
+
+    let s = Stream::new(&c, 0).unwrap();
+    assert_eq!(
+        Ok(()),
+        v.upload(&s, 0, SIZE, sys::VIR_STORAGE_VOL_UPLOAD_SPARSE_STREAM)
+    );
+    assert_eq!(Ok(()), s.sparse_send_file(&src));
+    assert_eq!(Ok(()), s.finish());
+
+    let mut dst = temp_file("sparse-dst");
+    let s = Stream::new(&c, 0).unwrap();
+    assert_eq!(
+        Ok(()),
+        v.download(&s, 0, SIZE, sys::VIR_STORAGE_VOL_DOWNLOAD_SPARSE_STREAM)
+    );
+    assert_eq!(Ok(()), s.sparse_recv_file(&dst));
+    assert_eq!(Ok(()), s.finish());
+
+    let mut expected = Vec::new();
+    src.seek(SeekFrom::Start(0)).unwrap();
+    src.read_to_end(&mut expected).unwrap();
+    let mut received = Vec::new();
+    dst.seek(SeekFrom::Start(0)).unwrap();
+    dst.read_to_end(&mut received).unwrap();
+    assert_eq!(expected, received);
+    // The hole was skipped rather than written as zeroes.
+    assert!(dst.metadata().unwrap().blocks() * 512 < SIZE);
+
+    common::clean_vol(v);
+    common::clean_pool(p);
+    common::close(c);
+}
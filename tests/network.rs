@@ -18,6 +18,11 @@
 
 mod common;
 
+use std::sync::{Arc, Mutex};
+
+use virt::event;
+use virt::network::{NetworkEvent, NetworkEventId, NetworkEventLifecycleType};
+
 #[test]
 fn test_create() {
     let c = common::conn();
@@ -61,3 +66,28 @@ fn test_lookup_network_by_name() {
     assert!(!v.is_empty(), "At least one network should exist");
     common::close(c);
 }
+
+#[test]
+fn test_network_event_lifecycle() {
+    event::event_register_default_impl().unwrap();
+    let c = common::conn();
+    let n = common::build_network(&c, "event_lifecycle", false);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    let cb = c
+        .network_event_register_any(Some(&n), NetworkEventId::Lifecycle, move |_, _, ev| {
+            if let NetworkEvent::Lifecycle { event, .. } = ev {
+                seen.lock().unwrap().push(event);
+            }
+        })
+        .unwrap();
+    assert_eq!(Ok(()), n.create());
+    assert_eq!(Ok(()), n.destroy());
+    common::run_event_loop_until(|| events.lock().unwrap().len() >= 2);
+    let events = events.lock().unwrap();
+    assert!(events[0].is(NetworkEventLifecycleType::Started));
+    assert!(events[1].is(NetworkEventLifecycleType::Stopped));
+    assert_eq!(Ok(()), cb.deregister());
+    common::clean_net(n);
+    common::close(c);
+}
@@ -16,7 +16,12 @@
  * Sahid Orentino Ferdjaoui <sahid.ferdjaoui@redhat.com>
  */
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
 use virt::connect::Connect;
+use virt::event;
+use virt::storage_pool::{StoragePoolEvent, StoragePoolEventId};
 
 mod common;
 
@@ -52,3 +57,24 @@ fn test_lookup_storage_pool_by_name() {
     }
     common::close(c);
 }
+
+#[test]
+fn test_storage_pool_event_refresh() {
+    event::event_register_default_impl().unwrap();
+    let c = common::conn();
+    let p = common::build_storage_pool(&c, "event_refresh", true);
+    let refreshed = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&refreshed);
+    let cb = c
+        .storage_pool_event_register_any(Some(&p), StoragePoolEventId::Refresh, move |_, _, ev| {
+            if let StoragePoolEvent::Refresh = ev {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .unwrap();
+    assert_eq!(Ok(()), p.refresh(0));
+    common::run_event_loop_until(|| refreshed.load(Ordering::SeqCst) >= 1);
+    assert_eq!(Ok(()), cb.deregister());
+    common::clean_pool(p);
+    common::close(c);
+}
@@ -18,6 +18,12 @@
 
 mod common;
 
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use virt::event;
 use virt::stream::Stream;
 use virt::sys;
 
@@ -36,3 +42,85 @@ fn test_create_non_blocking() {
     drop(s);
     common::close(c);
 }
+
+#[test]
+fn test_io_copy_screenshot() {
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "io_copy", false);
+    assert_eq!(Ok(()), d.create_with_flags(0));
+
+    let mut s = Stream::new(&c, 0).unwrap();
+    assert_eq!(Ok(String::from("image/png")), d.screenshot(&s, 0, 0));
+    let mut png = Vec::new();
+    let n = io::copy(&mut s, &mut png).unwrap();
+    assert_eq!(png.len() as u64, n);
+    assert_eq!(b"\x89PNG", &png[..4]);
+    assert_eq!(Ok(()), s.finish());
+
+    common::clean_dom(d);
+    common::close(c);
+}
+
+#[test]
+fn test_recv_all_screenshot() {
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "recv_all", false);
+    assert_eq!(Ok(()), d.create_with_flags(0));
+
+    let s = Stream::new(&c, 0).unwrap();
+    assert_eq!(Ok(String::from("image/png")), d.screenshot(&s, 0, 0));
+    let mut png = Vec::new();
+    assert_eq!(
+        Ok(()),
+        s.recv_all(|buf| {
+            png.extend_from_slice(buf);
+            Ok(buf.len())
+        })
+    );
+    assert_eq!(b"\x89PNG", &png[..4]);
+    assert_eq!(Ok(()), s.finish());
+
+    let s = Stream::new(&c, 0).unwrap();
+    assert_eq!(Ok(String::from("image/png")), d.screenshot(&s, 0, 0));
+    let err = s
+        .recv_all(|_| Err(io::Error::new(io::ErrorKind::Other, "sink full")))
+        .unwrap_err();
+    assert_eq!("sink full", err.message());
+
+    // Panics are carried over libvirt back to the caller.
+    let s = Stream::new(&c, 0).unwrap();
+    assert_eq!(Ok(String::from("image/png")), d.screenshot(&s, 0, 0));
+    let res = panic::catch_unwind(AssertUnwindSafe(|| s.recv_all(|_| panic!("sink panicked"))));
+    assert!(res.is_err());
+
+    common::clean_dom(d);
+    common::close(c);
+}
+
+#[test]
+fn test_event_callback_survives_move() {
+    event::event_register_default_impl().unwrap();
+    let c = common::conn();
+    let d = common::build_test_domain(&c, "stream_callback", false);
+    assert_eq!(Ok(()), d.create_with_flags(0));
+
+    let s = Stream::new(&c, sys::VIR_STREAM_NONBLOCK).unwrap();
+    assert_eq!(Ok(String::from("image/png")), d.screenshot(&s, 0, 0));
+    let fired = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&fired);
+    s.event_add_callback(sys::VIR_STREAM_EVENT_READABLE, move |_, _| {
+        seen.fetch_add(1, Ordering::SeqCst);
+    })
+    .unwrap();
+
+    // Moving the stream and dropping a clone must keep the callback.
+    let streams = vec![s.clone(), s];
+    let s = streams.into_iter().nth(1).unwrap();
+    common::run_event_loop_until(|| fired.load(Ordering::SeqCst) > 0);
+    assert_eq!(Ok(()), s.event_remove_callback());
+    assert!(s.event_remove_callback().is_err());
+    assert_eq!(Ok(()), s.abort());
+
+    common::clean_dom(d);
+    common::close(c);
+}
@@ -0,0 +1,114 @@
+/*
+ * This library is free software; you can redistribute it and/or
+ * modify it under the terms of the GNU Lesser General Public
+ * License as published by the Free Software Foundation; either
+ * version 2.1 of the License, or (at your option) any later version.
+ *
+ * This library is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+ * Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public
+ * License along with this library.  If not, see
+ * <https://www.gnu.org/licenses/>.
+ */
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::sync::Once;
+use std::thread;
+
+use tokio::io::AsyncReadExt;
+use virt::domain::{DomainEvent, DomainEventId, DomainEventType};
+use virt::stream::{AsyncStream, Stream};
+use virt::sys;
+use virt::tokio_event::TokioEventLoop;
+
+// The event loop implementation is process wide while every test has
+// its own runtime, so the loop gets a runtime of its own running in
+// the background for the whole process.
+fn register_event_loop() {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async { TokioEventLoop::register().unwrap() });
+        thread::spawn(move || rt.block_on(std::future::pending::<()>()));
+    });
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    register_event_loop();
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_tokio_domain_events() {
+    let rt = runtime();
+    rt.block_on(async {
+        let c = common::conn();
+        let d = common::build_test_domain(&c, "tokio_events", false);
+
+        let mut events = c
+            .domain_event_receiver(Some(&d), DomainEventId::Lifecycle)
+            .unwrap();
+        assert_eq!(Ok(()), d.create_with_flags(0));
+        assert_eq!(Ok(()), d.suspend());
+        let mut seen = Vec::new();
+        while seen.len() < 2 {
+            let (dom, ev) = events.recv().await.unwrap();
+            assert_eq!(d.name(), dom.name());
+            if let DomainEvent::Lifecycle { event, .. } = ev {
+                seen.push(event);
+            }
+        }
+        assert!(seen[0].is(DomainEventType::Started));
+        assert!(seen[1].is(DomainEventType::Suspended));
+        drop(events);
+
+        // Only runs once domain_event() below registered its callback.
+        let resumed = tokio::spawn({
+            let d = d.clone();
+            async move { d.resume() }
+        });
+        let (_, ev) = c
+            .domain_event(Some(&d), DomainEventId::Lifecycle)
+            .await
+            .unwrap();
+        assert!(
+            matches!(ev, DomainEvent::Lifecycle { event, .. } if event.is(DomainEventType::Resumed))
+        );
+        assert_eq!(Ok(()), resumed.await.unwrap());
+
+        common::clean_dom(d);
+        common::close(c);
+    });
+}
+
+#[test]
+fn test_tokio_async_stream() {
+    let rt = runtime();
+    rt.block_on(async {
+        let c = common::conn();
+        let d = common::build_test_domain(&c, "tokio_stream", false);
+        assert_eq!(Ok(()), d.create_with_flags(0));
+
+        let s = Stream::new(&c, sys::VIR_STREAM_NONBLOCK).unwrap();
+        assert_eq!(Ok(String::from("image/png")), d.screenshot(&s, 0, 0));
+        let mut s = AsyncStream::new(s).unwrap();
+        let mut png = Vec::new();
+        s.read_to_end(&mut png).await.unwrap();
+        assert_eq!(b"\x89PNG", &png[..4]);
+
+        common::clean_dom(d);
+        common::close(c);
+    });
+}